@@ -0,0 +1,434 @@
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use crate::df::stats::{HistogramBin, histogram};
+
+const MAX_HISTOGRAM_BINS: usize = 200;
+const MAX_BAR_CATEGORIES: u32 = 50;
+const MAX_SERIES: usize = 10;
+const MAX_LINE_POINTS: usize = 4_000;
+const MAX_SCATTER_POINTS: usize = 20_000;
+// Label of the null category, kept apart from a real "null" value
+const NULL_LABEL: &str = "(null)";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartType {
+    Histogram,
+    Bar,
+    Line,
+    Scatter,
+}
+
+impl ChartType {
+    pub const ALL: [ChartType; 4] = [
+        ChartType::Histogram,
+        ChartType::Bar,
+        ChartType::Line,
+        ChartType::Scatter,
+    ];
+
+    pub fn uses_y(&self) -> bool {
+        !matches!(self, ChartType::Histogram)
+    }
+
+    pub fn requires_y(&self) -> bool {
+        matches!(self, ChartType::Line | ChartType::Scatter)
+    }
+
+    pub fn uses_color(&self) -> bool {
+        matches!(self, ChartType::Line | ChartType::Scatter)
+    }
+}
+
+impl std::fmt::Display for ChartType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChartType::Histogram => write!(f, "Histogram"),
+            ChartType::Bar => write!(f, "Bar"),
+            ChartType::Line => write!(f, "Line"),
+            ChartType::Scatter => write!(f, "Scatter"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartConfig {
+    pub chart_type: ChartType,
+    pub x_column: String,
+    pub y_column: Option<String>,
+    pub color_column: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChartSeries {
+    pub name: String,
+    pub points: Vec<[f64; 2]>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ChartData {
+    Histogram(Vec<HistogramBin>),
+    // Category Label and Value (Sum of Y or Row Count)
+    Bar(Vec<(String, f64)>),
+    Line(Vec<ChartSeries>),
+    Scatter(Vec<ChartSeries>),
+}
+
+/// Y axis label, the count for histograms and bars without a Y column.
+pub fn y_label(data: &ChartData, config: &ChartConfig) -> String {
+    match (data, &config.y_column) {
+        (ChartData::Histogram(_), _) | (ChartData::Bar(_), None) => "Count".to_string(),
+        (_, Some(y)) => y.clone(),
+        (_, None) => String::new(),
+    }
+}
+
+/// Builds the plottable data for the chart configuration from the DataFrame.
+pub fn build_chart(df: &DataFrame, config: &ChartConfig) -> Result<ChartData, String> {
+    match config.chart_type {
+        ChartType::Histogram => histogram_data(df, &config.x_column),
+        ChartType::Bar => bar_data(df, &config.x_column, config.y_column.as_deref()),
+        ChartType::Line | ChartType::Scatter => {
+            let y_column = config
+                .y_column
+                .as_deref()
+                .ok_or_else(|| format!("{} chart requires a Y column", config.chart_type))?;
+            let series = series_data(
+                df,
+                &config.x_column,
+                y_column,
+                config.color_column.as_deref(),
+            )?;
+            if config.chart_type == ChartType::Line {
+                Ok(ChartData::Line(
+                    series
+                        .into_iter()
+                        .map(|mut s| {
+                            s.points.sort_by(|a, b| a[0].total_cmp(&b[0]));
+                            s.points = downsample_min_max(&s.points, MAX_LINE_POINTS);
+                            s
+                        })
+                        .collect(),
+                ))
+            } else {
+                let total: usize = series.iter().map(|s| s.points.len()).sum();
+                let stride = total.div_ceil(MAX_SCATTER_POINTS).max(1);
+                Ok(ChartData::Scatter(
+                    series
+                        .into_iter()
+                        .map(|mut s| {
+                            s.points = s.points.into_iter().step_by(stride).collect();
+                            s
+                        })
+                        .collect(),
+                ))
+            }
+        }
+    }
+}
+
+/// Casts a numeric, temporal or boolean column to f64 values (temporal as their physical value).
+pub fn numeric_values(df: &DataFrame, column_name: &str) -> Result<Vec<Option<f64>>, String> {
+    let column = df.column(column_name).map_err(|e| e.to_string())?;
+    let dtype = column.dtype();
+    let column = if dtype.is_primitive_numeric() || dtype.is_bool() {
+        column.clone()
+    } else if dtype.is_temporal() {
+        column.to_physical_repr()
+    } else {
+        return Err(format!(
+            "Column '{}' is not numeric ({})",
+            column_name, dtype
+        ));
+    };
+
+    let values = column.cast(&DataType::Float64).map_err(|e| e.to_string())?;
+    let values = values.f64().map_err(|e| e.to_string())?;
+    Ok(values.into_iter().collect())
+}
+
+fn histogram_data(df: &DataFrame, column_name: &str) -> Result<ChartData, String> {
+    let mut values: Vec<f64> = numeric_values(df, column_name)?
+        .into_iter()
+        .flatten()
+        .filter(|v| v.is_finite())
+        .collect();
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    Ok(ChartData::Histogram(histogram(
+        &values,
+        histogram_bin_count(&values),
+    )))
+}
+
+// Freedman-Diaconis Rule, falling back to Sturges' Rule when the IQR is zero
+fn histogram_bin_count(sorted: &[f64]) -> usize {
+    let n = sorted.len();
+    if n < 2 {
+        return 1;
+    }
+    let sturges = (n as f64).log2().ceil() as usize + 1;
+
+    let q1 = sorted[n / 4];
+    let q3 = sorted[(3 * n) / 4];
+    let iqr = q3 - q1;
+    let range = sorted[n - 1] - sorted[0];
+    if iqr <= 0.0 || range <= 0.0 {
+        return sturges.min(MAX_HISTOGRAM_BINS);
+    }
+
+    let width = 2.0 * iqr / (n as f64).cbrt();
+    ((range / width).ceil() as usize).clamp(1, MAX_HISTOGRAM_BINS)
+}
+
+fn bar_data(df: &DataFrame, x_column: &str, y_column: Option<&str>) -> Result<ChartData, String> {
+    let value = match y_column {
+        Some(y) => col(y).cast(DataType::Float64).sum(),
+        None => len().cast(DataType::Float64),
+    };
+
+    let grouped = df
+        .clone()
+        .lazy()
+        .group_by([col(x_column).cast(DataType::String).alias("__category")])
+        .agg([value.alias("__value")])
+        .sort(
+            ["__value"],
+            SortMultipleOptions::default().with_order_descending(true),
+        )
+        .limit(MAX_BAR_CATEGORIES)
+        .collect()
+        .map_err(|e| e.to_string())?;
+
+    let categories = grouped
+        .column("__category")
+        .and_then(|c| c.str().cloned())
+        .map_err(|e| e.to_string())?;
+    let values = grouped
+        .column("__value")
+        .and_then(|c| c.f64().cloned())
+        .map_err(|e| e.to_string())?;
+
+    Ok(ChartData::Bar(
+        categories
+            .iter()
+            .zip(values.iter())
+            .map(|(category, value)| {
+                (
+                    category.unwrap_or(NULL_LABEL).to_string(),
+                    value.unwrap_or(0.0),
+                )
+            })
+            .collect(),
+    ))
+}
+
+fn series_data(
+    df: &DataFrame,
+    x_column: &str,
+    y_column: &str,
+    color_column: Option<&str>,
+) -> Result<Vec<ChartSeries>, String> {
+    let xs = numeric_values(df, x_column)?;
+    let ys = numeric_values(df, y_column)?;
+
+    let Some(color_column) = color_column else {
+        return Ok(vec![ChartSeries {
+            name: y_column.to_string(),
+            points: zip_points(&xs, &ys, |_| true),
+        }]);
+    };
+
+    // Largest groups get their own series, the remainder are combined into "Other"
+    let groups = df
+        .column(color_column)
+        .and_then(|c| c.cast(&DataType::String))
+        .map_err(|e| e.to_string())?;
+    let groups = groups.str().map_err(|e| e.to_string())?;
+    let counts = crate::df::frequency::value_counts(df, color_column).map_err(|e| e.to_string())?;
+    // Groups are keyed by value, so nulls stay apart from a real "null" group
+    let named: Vec<Option<String>> = counts
+        .iter()
+        .take(MAX_SERIES)
+        .map(|vc| vc.value.clone())
+        .collect();
+    // Series index of every row, None for the remainder, so a real "Other" group stays apart
+    let series_indices: HashMap<Option<&str>, usize> = named
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.as_deref(), i))
+        .collect();
+    let row_series: Vec<Option<usize>> = groups
+        .into_iter()
+        .map(|g| series_indices.get(&g).copied())
+        .collect();
+    let other_name = if series_indices.contains_key(&Some("Other")) {
+        "Other (remaining groups)"
+    } else {
+        "Other"
+    };
+
+    let mut series: Vec<ChartSeries> = named
+        .iter()
+        .enumerate()
+        .map(|(i, name)| ChartSeries {
+            name: name.as_deref().unwrap_or(NULL_LABEL).to_string(),
+            points: zip_points(&xs, &ys, |row| row_series[row] == Some(i)),
+        })
+        .chain(std::iter::once(ChartSeries {
+            name: other_name.to_string(),
+            points: zip_points(&xs, &ys, |row| row_series[row].is_none()),
+        }))
+        .collect();
+    series.retain(|s| !s.points.is_empty());
+    Ok(series)
+}
+
+fn zip_points(
+    xs: &[Option<f64>],
+    ys: &[Option<f64>],
+    include: impl Fn(usize) -> bool,
+) -> Vec<[f64; 2]> {
+    xs.iter()
+        .zip(ys.iter())
+        .enumerate()
+        .filter(|(i, _)| include(*i))
+        .filter_map(|(_, (x, y))| Some([(*x)?, (*y)?]))
+        .filter(|[x, y]| x.is_finite() && y.is_finite())
+        .collect()
+}
+
+// Keeps the minimum and maximum Y of each bucket so peaks survive downsampling
+fn downsample_min_max(points: &[[f64; 2]], max_points: usize) -> Vec<[f64; 2]> {
+    if points.len() <= max_points {
+        return points.to_vec();
+    }
+
+    let bucket_size = points.len().div_ceil(max_points / 2);
+    points
+        .chunks(bucket_size)
+        .flat_map(|bucket| {
+            let min = bucket.iter().min_by(|a, b| a[1].total_cmp(&b[1])).copied();
+            let max = bucket.iter().max_by(|a, b| a[1].total_cmp(&b[1])).copied();
+            match (min, max) {
+                (Some(min), Some(max)) if min[0] <= max[0] => vec![min, max],
+                (Some(min), Some(max)) => vec![max, min],
+                _ => Vec::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freedman_diaconis_bin_count() {
+        let values: Vec<f64> = (1..=100).map(f64::from).collect();
+        // IQR 50 over n^(1/3) gives bins of 21.5 across a range of 99
+        assert_eq!(histogram_bin_count(&values), 5);
+        // An outlier is capped
+        let mut outlier = values.clone();
+        outlier.push(1e9);
+        assert_eq!(histogram_bin_count(&outlier), MAX_HISTOGRAM_BINS);
+    }
+
+    #[test]
+    fn sturges_bin_count_without_spread() {
+        assert_eq!(histogram_bin_count(&[]), 1);
+        assert_eq!(histogram_bin_count(&[3.0]), 1);
+        // Zero IQR, log2(8) + 1
+        assert_eq!(
+            histogram_bin_count(&[1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 9.0]),
+            4
+        );
+    }
+
+    #[test]
+    fn downsampling_keeps_peaks_in_order() {
+        let points: Vec<[f64; 2]> = (0..10_000)
+            .map(|i| [i as f64, if i == 5_003 { 100.0 } else { (i % 7) as f64 }])
+            .collect();
+        assert_eq!(
+            downsample_min_max(&points[..50], 100),
+            points[..50].to_vec()
+        );
+
+        let sampled = downsample_min_max(&points, 100);
+        assert!(sampled.len() <= 100);
+        assert!(sampled.contains(&[5_003.0, 100.0]));
+        assert!(sampled.windows(2).all(|pair| pair[0][0] <= pair[1][0]));
+    }
+
+    fn grouped(groups: Vec<Option<String>>) -> DataFrame {
+        let xs: Vec<f64> = (0..groups.len()).map(|i| i as f64).collect();
+        df!("x" => &xs, "y" => &xs, "group" => groups).unwrap()
+    }
+
+    fn series_sizes(series: &[ChartSeries]) -> Vec<(&str, usize)> {
+        series
+            .iter()
+            .map(|s| (s.name.as_str(), s.points.len()))
+            .collect()
+    }
+
+    #[test]
+    fn remaining_groups_are_combined_apart_from_other() {
+        // "Other" and g1..g11 with 20, 19, ... 9 rows
+        let groups = (0..12)
+            .flat_map(|i| {
+                let name = if i == 0 {
+                    "Other".to_string()
+                } else {
+                    format!("g{i}")
+                };
+                std::iter::repeat_n(Some(name), 20 - i)
+            })
+            .collect();
+        let series = series_data(&grouped(groups), "x", "y", Some("group")).unwrap();
+        let sizes = series_sizes(&series);
+        assert_eq!(sizes.len(), MAX_SERIES + 1);
+        assert_eq!(sizes[0], ("Other", 20));
+        assert_eq!(sizes[MAX_SERIES], ("Other (remaining groups)", 10 + 9));
+    }
+
+    #[test]
+    fn null_group_stays_apart_from_null_string() {
+        let groups = vec![
+            Some("null".to_string()),
+            Some("null".to_string()),
+            None,
+            Some("a".to_string()),
+        ];
+        let series = series_data(&grouped(groups), "x", "y", Some("group")).unwrap();
+        let mut sizes = series_sizes(&series);
+        sizes.sort();
+        assert_eq!(sizes, vec![(NULL_LABEL, 1), ("a", 1), ("null", 2)]);
+    }
+
+    #[test]
+    fn bar_null_category_is_labelled_apart() {
+        let df = df!("city" => [Some("null"), None, Some("null")]).unwrap();
+        let ChartData::Bar(mut bars) = bar_data(&df, "city", None).unwrap() else {
+            panic!("expected bars");
+        };
+        bars.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            bars,
+            vec![(NULL_LABEL.to_string(), 1.0), ("null".to_string(), 2.0)]
+        );
+    }
+
+    #[test]
+    fn histogram_y_label_is_count() {
+        let config = ChartConfig {
+            chart_type: ChartType::Histogram,
+            x_column: "x".to_string(),
+            y_column: Some("left over".to_string()),
+            color_column: None,
+        };
+        assert_eq!(y_label(&ChartData::Histogram(Vec::new()), &config), "Count");
+        assert_eq!(y_label(&ChartData::Bar(Vec::new()), &config), "left over");
+    }
+}
@@ -0,0 +1,68 @@
+use eframe::egui::{Response, Ui};
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, Points};
+
+use crate::chart::data::{ChartConfig, ChartData, y_label};
+use crate::ui::palette::categorical;
+
+/// Renders the interactive (zoom, pan, hover) plot, returning its response.
+pub fn render_chart(ui: &mut Ui, data: &ChartData, config: &ChartConfig) -> Response {
+    let y_label = y_label(data, config);
+
+    let mut plot = Plot::new("chart_plot")
+        .legend(Legend::default())
+        .x_axis_label(config.x_column.clone())
+        .y_axis_label(y_label);
+
+    if let ChartData::Bar(bars) = data {
+        let labels: Vec<String> = bars.iter().map(|(label, _)| label.clone()).collect();
+        plot = plot.x_axis_formatter(move |mark, _range| {
+            let index = mark.value.round();
+            if (mark.value - index).abs() > f64::EPSILON || index < 0.0 {
+                return String::new();
+            }
+            labels.get(index as usize).cloned().unwrap_or_default()
+        });
+    }
+
+    plot.show(ui, |plot_ui| match data {
+        ChartData::Histogram(bins) => {
+            let bars = bins
+                .iter()
+                .map(|bin| {
+                    Bar::new((bin.start + bin.end) / 2.0, bin.count as f64)
+                        .width(bin.end - bin.start)
+                        .name(format!("[{:.4}, {:.4})", bin.start, bin.end))
+                })
+                .collect();
+            plot_ui.bar_chart(BarChart::new(config.x_column.clone(), bars).color(categorical(0)));
+        }
+        ChartData::Bar(categories) => {
+            let bars = categories
+                .iter()
+                .enumerate()
+                .map(|(i, (label, value))| {
+                    Bar::new(i as f64, *value)
+                        .width(0.8)
+                        .name(label)
+                        .fill(categorical(i))
+                })
+                .collect();
+            plot_ui.bar_chart(BarChart::new(config.x_column.clone(), bars));
+        }
+        ChartData::Line(series) => {
+            for (i, s) in series.iter().enumerate() {
+                plot_ui.line(Line::new(s.name.clone(), s.points.clone()).color(categorical(i)));
+            }
+        }
+        ChartData::Scatter(series) => {
+            for (i, s) in series.iter().enumerate() {
+                plot_ui.points(
+                    Points::new(s.name.clone(), s.points.clone())
+                        .color(categorical(i))
+                        .radius(2.0),
+                );
+            }
+        }
+    })
+    .response
+}
@@ -0,0 +1,215 @@
+use eframe::egui::Color32;
+use std::fmt::Write;
+
+use crate::chart::data::{ChartConfig, ChartData, y_label};
+use crate::ui::palette::categorical;
+
+const WIDTH: f64 = 960.0;
+const HEIGHT: f64 = 600.0;
+const MARGIN: f64 = 60.0;
+
+// Maps Data Coordinates into the SVG Plot Area
+struct Frame {
+    x_min: f64,
+    x_max: f64,
+    y_min: f64,
+    y_max: f64,
+}
+
+impl Frame {
+    fn from_points(points: impl Iterator<Item = [f64; 2]>) -> Self {
+        let mut frame = Frame {
+            x_min: f64::INFINITY,
+            x_max: f64::NEG_INFINITY,
+            y_min: 0.0,
+            y_max: f64::NEG_INFINITY,
+        };
+        for [x, y] in points {
+            frame.x_min = frame.x_min.min(x);
+            frame.x_max = frame.x_max.max(x);
+            frame.y_min = frame.y_min.min(y);
+            frame.y_max = frame.y_max.max(y);
+        }
+        if !frame.x_min.is_finite() {
+            (frame.x_min, frame.x_max) = (0.0, 1.0);
+        }
+        if !frame.y_max.is_finite() {
+            frame.y_max = 1.0;
+        }
+        if frame.x_min == frame.x_max {
+            frame.x_max += 1.0;
+        }
+        if frame.y_min == frame.y_max {
+            frame.y_max += 1.0;
+        }
+        frame
+    }
+
+    fn x(&self, x: f64) -> f64 {
+        MARGIN + (x - self.x_min) / (self.x_max - self.x_min) * (WIDTH - 2.0 * MARGIN)
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        HEIGHT - MARGIN - (y - self.y_min) / (self.y_max - self.y_min) * (HEIGHT - 2.0 * MARGIN)
+    }
+}
+
+fn hex(color: Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders the chart as a standalone SVG document.
+pub fn chart_to_svg(data: &ChartData, config: &ChartConfig) -> String {
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="12">"#
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    let frame = match data {
+        ChartData::Histogram(bins) => {
+            let frame = Frame::from_points(
+                bins.iter()
+                    .flat_map(|b| [[b.start, b.count as f64], [b.end, 0.0]]),
+            );
+            for bin in bins {
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}"/>"#,
+                    frame.x(bin.start),
+                    frame.y(bin.count as f64),
+                    frame.x(bin.end) - frame.x(bin.start),
+                    frame.y(0.0) - frame.y(bin.count as f64),
+                    hex(categorical(0))
+                );
+            }
+            frame
+        }
+        ChartData::Bar(bars) => {
+            let frame = Frame::from_points(
+                bars.iter()
+                    .enumerate()
+                    .flat_map(|(i, (_, v))| [[i as f64 - 0.5, *v], [i as f64 + 0.5, 0.0]]),
+            );
+            for (i, (label, value)) in bars.iter().enumerate() {
+                let left = frame.x(i as f64 - 0.4);
+                let right = frame.x(i as f64 + 0.4);
+                let top = frame.y(value.max(0.0));
+                let bottom = frame.y(value.min(0.0));
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{left:.2}" y="{top:.2}" width="{:.2}" height="{:.2}" fill="{}"><title>{}: {value}</title></rect>"#,
+                    right - left,
+                    bottom - top,
+                    hex(categorical(i)),
+                    escape(label)
+                );
+                let _ = writeln!(
+                    svg,
+                    r#"<text x="{:.2}" y="{:.2}" text-anchor="end" transform="rotate(-45 {:.2} {:.2})">{}</text>"#,
+                    (left + right) / 2.0,
+                    HEIGHT - MARGIN + 14.0,
+                    (left + right) / 2.0,
+                    HEIGHT - MARGIN + 14.0,
+                    escape(label)
+                );
+            }
+            frame
+        }
+        ChartData::Line(series) | ChartData::Scatter(series) => {
+            let frame = Frame::from_points(series.iter().flat_map(|s| s.points.iter().copied()));
+            let connected = matches!(data, ChartData::Line(_));
+            for (i, s) in series.iter().enumerate() {
+                let color = hex(categorical(i));
+                if connected {
+                    let path: Vec<String> = s
+                        .points
+                        .iter()
+                        .map(|[x, y]| format!("{:.2},{:.2}", frame.x(*x), frame.y(*y)))
+                        .collect();
+                    let _ = writeln!(
+                        svg,
+                        r#"<polyline points="{}" fill="none" stroke="{color}" stroke-width="1.5"/>"#,
+                        path.join(" ")
+                    );
+                } else {
+                    for [x, y] in &s.points {
+                        let _ = writeln!(
+                            svg,
+                            r#"<circle cx="{:.2}" cy="{:.2}" r="2" fill="{color}"/>"#,
+                            frame.x(*x),
+                            frame.y(*y)
+                        );
+                    }
+                }
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{:.2}" y="{:.2}" width="10" height="10" fill="{color}"/><text x="{:.2}" y="{:.2}">{}</text>"#,
+                    WIDTH - MARGIN - 120.0,
+                    MARGIN + 16.0 * i as f64,
+                    WIDTH - MARGIN - 105.0,
+                    MARGIN + 16.0 * i as f64 + 10.0,
+                    escape(&s.name)
+                );
+            }
+            frame
+        }
+    };
+
+    // Axes with their extents and labels
+    let _ = writeln!(
+        svg,
+        r#"<line x1="{MARGIN}" y1="{y:.2}" x2="{:.2}" y2="{y:.2}" stroke="black"/><line x1="{MARGIN}" y1="{MARGIN}" x2="{MARGIN}" y2="{:.2}" stroke="black"/>"#,
+        WIDTH - MARGIN,
+        HEIGHT - MARGIN,
+        y = HEIGHT - MARGIN,
+    );
+    if !matches!(data, ChartData::Bar(_)) {
+        let _ = writeln!(
+            svg,
+            r#"<text x="{MARGIN}" y="{:.2}">{:.4}</text><text x="{:.2}" y="{:.2}" text-anchor="end">{:.4}</text>"#,
+            HEIGHT - MARGIN + 16.0,
+            frame.x_min,
+            WIDTH - MARGIN,
+            HEIGHT - MARGIN + 16.0,
+            frame.x_max
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.2}" y="{:.2}" text-anchor="end">{:.4}</text><text x="{:.2}" y="{MARGIN}" text-anchor="end">{:.4}</text>"#,
+        MARGIN - 4.0,
+        HEIGHT - MARGIN,
+        frame.y_min,
+        MARGIN - 4.0,
+        frame.y_max
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.2}" y="{:.2}" text-anchor="middle">{}</text>"#,
+        WIDTH / 2.0,
+        HEIGHT - 8.0,
+        escape(&config.x_column)
+    );
+    let y_label = y_label(data, config);
+    if !y_label.is_empty() {
+        let _ = writeln!(
+            svg,
+            r#"<text x="14" y="{:.2}" text-anchor="middle" transform="rotate(-90 14 {:.2})">{}</text>"#,
+            HEIGHT / 2.0,
+            HEIGHT / 2.0,
+            escape(&y_label)
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
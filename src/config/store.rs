@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Returns the Parqr data directory (`~/.parqr`), creating it if required.
+pub fn app_data_dir() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    let dir = PathBuf::from(home).join(".parqr");
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir)
+}
+
+// Tab Separated Key/Value Store persisted in the Data Directory
+pub struct KeyValueStore {
+    path: Option<PathBuf>,
+    entries: BTreeMap<String, String>,
+}
+
+impl KeyValueStore {
+    pub fn open(file_name: &str) -> Self {
+        Self::load(app_data_dir().map(|dir| dir.join(file_name)))
+    }
+
+    fn load(path: Option<PathBuf>) -> Self {
+        let entries = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter_map(|line| line.split_once('\t'))
+                    .map(|(k, v)| (unescape(k), unescape(v)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { path, entries }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    /// Sets the value and writes the store to disk (best effort).
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.entries.insert(key.to_string(), value.to_string());
+        if let Some(path) = &self.path {
+            let contents: String = self
+                .entries
+                .iter()
+                .map(|(k, v)| format!("{}\t{}\n", escape(k), escape(v)))
+                .collect();
+            let _ = fs::write(path, contents);
+        }
+    }
+}
+
+// Escapes the separators (tabs and newlines) and backslashes
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Stable (FNV-1a) hash of the text, used for persistence keys.
+pub fn stable_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_round_trip_through_file() {
+        let path = std::env::temp_dir().join(format!("parqr-store-{}.tsv", std::process::id()));
+        let entries = [
+            ("plain", "value"),
+            ("tab\tkey", "tab\tvalue"),
+            ("new\nline", "multi\nline\r\nvalue"),
+            ("back\\slash\\t", "ends with \\"),
+            ("empty", ""),
+        ];
+
+        let mut store = KeyValueStore::load(Some(path.clone()));
+        for (key, value) in entries {
+            store.set(key, value);
+        }
+        // One line per entry
+        assert_eq!(
+            fs::read_to_string(&path).unwrap().lines().count(),
+            entries.len()
+        );
+
+        let reopened = KeyValueStore::load(Some(path.clone()));
+        for (key, value) in entries {
+            assert_eq!(reopened.get(key), Some(value), "{key:?}");
+        }
+        assert_eq!(reopened.get("missing"), None);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn escaping_is_reversible() {
+        for text in ["", "a\tb", "\\t", "\\", "x\ny\\n", "\u{1f}sep"] {
+            assert!(!escape(text).contains(['\t', '\n']));
+            assert_eq!(unescape(&escape(text)), text);
+        }
+    }
+
+    #[test]
+    fn stable_hash_is_fnv1a() {
+        assert_eq!(stable_hash(""), "cbf29ce484222325");
+        assert_eq!(stable_hash("a"), "af63dc4c8601ec8c");
+    }
+}
@@ -0,0 +1,273 @@
+use polars::prelude::*;
+
+// Maximum number of distinct Pivot Values turned into Columns
+const MAX_PIVOT_VALUES: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AggFunction {
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Median,
+    NUnique,
+    First,
+    Last,
+}
+
+impl AggFunction {
+    pub const ALL: [AggFunction; 9] = [
+        AggFunction::Count,
+        AggFunction::Sum,
+        AggFunction::Mean,
+        AggFunction::Min,
+        AggFunction::Max,
+        AggFunction::Median,
+        AggFunction::NUnique,
+        AggFunction::First,
+        AggFunction::Last,
+    ];
+
+    pub fn apply(&self, expr: Expr) -> Expr {
+        match self {
+            AggFunction::Count => expr.count(),
+            AggFunction::Sum => expr.sum(),
+            AggFunction::Mean => expr.mean(),
+            AggFunction::Min => expr.min(),
+            AggFunction::Max => expr.max(),
+            AggFunction::Median => expr.median(),
+            AggFunction::NUnique => expr.n_unique(),
+            AggFunction::First => expr.first(),
+            AggFunction::Last => expr.last(),
+        }
+    }
+}
+
+impl std::fmt::Display for AggFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggFunction::Count => write!(f, "count"),
+            AggFunction::Sum => write!(f, "sum"),
+            AggFunction::Mean => write!(f, "mean"),
+            AggFunction::Min => write!(f, "min"),
+            AggFunction::Max => write!(f, "max"),
+            AggFunction::Median => write!(f, "median"),
+            AggFunction::NUnique => write!(f, "n_unique"),
+            AggFunction::First => write!(f, "first"),
+            AggFunction::Last => write!(f, "last"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aggregation {
+    pub column_name: String,
+    pub function: AggFunction,
+}
+
+impl Aggregation {
+    fn output_name(&self) -> String {
+        format!("{}_{}", self.column_name, self.function)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GroupBySpec {
+    pub keys: Vec<String>,
+    pub aggregations: Vec<Aggregation>,
+    // Distinct values of the Pivot Column are spread into separate Columns
+    pub pivot_column: Option<String>,
+}
+
+/// Groups the DataFrame by the keys and computes the aggregations (pivoted if configured).
+pub fn aggregate_dataframe(dataframe: &DataFrame, spec: &GroupBySpec) -> PolarsResult<DataFrame> {
+    polars_ensure!(
+        !spec.aggregations.is_empty(),
+        InvalidOperation: "at least one aggregation is required"
+    );
+
+    let agg_exprs = match &spec.pivot_column {
+        Some(pivot_column) => pivot_exprs(dataframe, pivot_column, &spec.aggregations)?,
+        None => spec
+            .aggregations
+            .iter()
+            .map(|agg| {
+                agg.function
+                    .apply(col(&agg.column_name))
+                    .alias(agg.output_name())
+            })
+            .collect(),
+    };
+
+    let lazy_df = dataframe.clone().lazy();
+    let aggregated = if spec.keys.is_empty() {
+        lazy_df.select(agg_exprs)
+    } else {
+        let keys: Vec<Expr> = spec.keys.iter().map(col).collect();
+        lazy_df
+            .group_by(keys)
+            .agg(agg_exprs)
+            .sort(spec.keys.clone(), SortMultipleOptions::default())
+    };
+
+    aggregated.collect()
+}
+
+fn pivot_exprs(
+    dataframe: &DataFrame,
+    pivot_column: &str,
+    aggregations: &[Aggregation],
+) -> PolarsResult<Vec<Expr>> {
+    let pivot_values = dataframe
+        .column(pivot_column)?
+        .cast(&DataType::String)?
+        .unique_stable()?;
+    let pivot_values = pivot_values.str()?;
+    polars_ensure!(
+        pivot_values.len() <= MAX_PIVOT_VALUES,
+        InvalidOperation: "pivot column '{}' has {} distinct values (maximum {})",
+        pivot_column, pivot_values.len(), MAX_PIVOT_VALUES
+    );
+
+    let mut exprs = Vec::new();
+    for pivot_value in pivot_values.iter() {
+        let (matches, label) = match pivot_value {
+            Some(value) => (
+                col(pivot_column)
+                    .cast(DataType::String)
+                    .eq(lit(value.to_string())),
+                value.to_string(),
+            ),
+            None => (col(pivot_column).is_null(), "null".to_string()),
+        };
+        for agg in aggregations {
+            let filtered = col(&agg.column_name).filter(matches.clone());
+            exprs.push(agg.function.apply(filtered).alias(format!(
+                "{}_{}",
+                label,
+                agg.output_name()
+            )));
+        }
+    }
+    Ok(exprs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sales() -> DataFrame {
+        df!(
+            "city" => [Some("Oslo"), Some("Bergen"), None, Some("Oslo"), None],
+            "year" => [Some(2023), Some(2024), Some(2023), Some(2024), None],
+            "amount" => [1.0, 2.0, 4.0, 8.0, 16.0],
+        )
+        .unwrap()
+    }
+
+    fn agg(column_name: &str, function: AggFunction) -> Aggregation {
+        Aggregation {
+            column_name: column_name.to_string(),
+            function,
+        }
+    }
+
+    #[test]
+    fn group_by_keeps_null_key_group() {
+        let spec = GroupBySpec {
+            keys: vec!["city".to_string()],
+            aggregations: vec![
+                agg("amount", AggFunction::Sum),
+                agg("amount", AggFunction::Count),
+            ],
+            pivot_column: None,
+        };
+        let result = aggregate_dataframe(&sales(), &spec).unwrap();
+
+        let cities: Vec<Option<&str>> = result
+            .column("city")
+            .unwrap()
+            .str()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(cities, vec![None, Some("Bergen"), Some("Oslo")]);
+        let sums: Vec<Option<f64>> = result
+            .column("amount_sum")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(sums, vec![Some(20.0), Some(2.0), Some(9.0)]);
+        let counts: Vec<Option<u32>> = result
+            .column("amount_count")
+            .unwrap()
+            .u32()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(counts, vec![Some(2), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn pivot_spreads_values_into_columns() {
+        let spec = GroupBySpec {
+            keys: vec!["city".to_string()],
+            aggregations: vec![agg("amount", AggFunction::Sum)],
+            pivot_column: Some("year".to_string()),
+        };
+        let result = aggregate_dataframe(&sales(), &spec).unwrap();
+
+        let names: Vec<&str> = result
+            .get_column_names()
+            .iter()
+            .map(|n| n.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "city",
+                "2023_amount_sum",
+                "2024_amount_sum",
+                "null_amount_sum"
+            ]
+        );
+        let column = |name: &str| -> Vec<Option<f64>> {
+            result.column(name).unwrap().f64().unwrap().iter().collect()
+        };
+        // Rows: null city, Bergen, Oslo
+        assert_eq!(
+            column("2023_amount_sum"),
+            vec![Some(4.0), Some(0.0), Some(1.0)]
+        );
+        assert_eq!(
+            column("2024_amount_sum"),
+            vec![Some(0.0), Some(2.0), Some(8.0)]
+        );
+        assert_eq!(
+            column("null_amount_sum"),
+            vec![Some(16.0), Some(0.0), Some(0.0)]
+        );
+    }
+
+    #[test]
+    fn without_keys_aggregates_whole_frame() {
+        let spec = GroupBySpec {
+            aggregations: vec![agg("amount", AggFunction::Max)],
+            ..Default::default()
+        };
+        let result = aggregate_dataframe(&sales(), &spec).unwrap();
+        assert_eq!(result.height(), 1);
+        assert_eq!(
+            result.column("amount_max").unwrap().f64().unwrap().get(0),
+            Some(16.0)
+        );
+    }
+
+    #[test]
+    fn aggregation_is_required() {
+        assert!(aggregate_dataframe(&sales(), &GroupBySpec::default()).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use polars::prelude::*;
+use polars::sql::sql_expr;
+
+// Column Derived from a SQL Expression (e.g. `price * qty`)
+#[derive(Debug, Clone)]
+pub struct ComputedColumn {
+    pub name: String,
+    pub expression: String,
+}
+
+impl ComputedColumn {
+    fn expr(&self) -> PolarsResult<Expr> {
+        Ok(sql_expr(&self.expression)?.alias(self.name.as_str()))
+    }
+}
+
+/// Names of the computed columns whose expressions reference the column.
+pub fn dependent_columns<'a>(columns: &'a [ComputedColumn], column_name: &str) -> Vec<&'a str> {
+    columns
+        .iter()
+        .filter(|column| {
+            sql_expr(&column.expression).is_ok_and(|expr| {
+                expr.meta()
+                    .root_names()
+                    .iter()
+                    .any(|name| name.as_str() == column_name)
+            })
+        })
+        .map(|column| column.name.as_str())
+        .collect()
+}
+
+/// Checks the expression parses and resolves against the DataFrame schema, returning its type.
+pub fn validate_computed_column(
+    dataframe: &DataFrame,
+    column: &ComputedColumn,
+) -> PolarsResult<DataType> {
+    polars_ensure!(
+        !column.name.trim().is_empty(),
+        InvalidOperation: "column name is required"
+    );
+    polars_ensure!(
+        dataframe.column(&column.name).is_err(),
+        Duplicate: "column '{}' already exists", column.name
+    );
+
+    let mut lazy_df = dataframe.clone().lazy().select([column.expr()?]);
+    let schema = lazy_df.collect_schema()?;
+    schema
+        .get(column.name.as_str())
+        .cloned()
+        .ok_or_else(|| polars_err!(ColumnNotFound: "{}", column.name))
+}
+
+/// Appends the computed columns to the DataFrame.
+pub fn add_computed_columns(
+    dataframe: &DataFrame,
+    columns: &[ComputedColumn],
+) -> PolarsResult<DataFrame> {
+    let exprs = columns
+        .iter()
+        .map(ComputedColumn::expr)
+        .collect::<PolarsResult<Vec<_>>>()?;
+    dataframe.clone().lazy().with_columns(exprs).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computed(name: &str, expression: &str) -> ComputedColumn {
+        ComputedColumn {
+            name: name.to_string(),
+            expression: expression.to_string(),
+        }
+    }
+
+    fn orders() -> DataFrame {
+        df!("price" => [2.0, 3.0], "qty" => [4i64, 5]).unwrap()
+    }
+
+    #[test]
+    fn expression_is_evaluated() {
+        let total = computed("total", "price * qty");
+        assert_eq!(
+            validate_computed_column(&orders(), &total).unwrap(),
+            DataType::Float64
+        );
+        let result = add_computed_columns(&orders(), &[total]).unwrap();
+        let totals: Vec<Option<f64>> = result
+            .column("total")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(totals, vec![Some(8.0), Some(15.0)]);
+    }
+
+    #[test]
+    fn invalid_expression_is_an_error() {
+        let df = orders();
+        assert!(validate_computed_column(&df, &computed("total", "price *")).is_err());
+        assert!(validate_computed_column(&df, &computed("total", "missing + 1")).is_err());
+        assert!(validate_computed_column(&df, &computed("price", "qty + 1")).is_err());
+        assert!(validate_computed_column(&df, &computed(" ", "qty + 1")).is_err());
+    }
+
+    #[test]
+    fn dependents_keep_their_source_column() {
+        let columns = [
+            computed("total", "price * qty"),
+            computed("taxed", "total * 1.25"),
+            computed("double", "qty * 2"),
+        ];
+        assert_eq!(dependent_columns(&columns, "total"), vec!["taxed"]);
+        assert_eq!(dependent_columns(&columns, "qty"), vec!["total", "double"]);
+        assert!(dependent_columns(&columns, "taxed").is_empty());
+    }
+}
@@ -5,6 +5,7 @@ use polars::{frame::DataFrame, prelude::DataType};
 pub enum FilterType {
     Equals,
     Contains,
+    InList,
 }
 
 impl std::fmt::Display for FilterType {
@@ -12,6 +13,7 @@ impl std::fmt::Display for FilterType {
         match self {
             FilterType::Equals => write!(f, "Equals"),
             FilterType::Contains => write!(f, "Contains"),
+            FilterType::InList => write!(f, "In List"),
         }
     }
 }
@@ -20,7 +22,12 @@ impl std::fmt::Display for FilterType {
 pub struct FilterCondition {
     pub filter_type: FilterType,
     pub column_name: String,
+    // Value of Equals/Contains Conditions, the value being added for InList Conditions
     pub filter_value: String,
+    // Values matched by InList Conditions, kept as is (values may contain commas)
+    pub list_values: Vec<String>,
+    // Whether InList Conditions also match nulls
+    pub include_null: bool,
 }
 
 pub fn filter_dataframe(
@@ -40,6 +47,11 @@ pub fn filter_dataframe(
                     .cast(DataType::String)
                     .str()
                     .contains(lit(filter.filter_value.clone()), false),
+                FilterType::InList => in_list_expr(
+                    &filter.column_name,
+                    &filter.list_values,
+                    filter.include_null,
+                ),
             };
             filter_exprs.push(expr);
         }
@@ -52,3 +64,147 @@ pub fn filter_dataframe(
         .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
     Ok(filtered_df)
 }
+
+// Nothing matches an empty list
+fn in_list_expr(column_name: &str, values: &[String], include_null: bool) -> Expr {
+    let matched = if values.is_empty() {
+        lit(false)
+    } else {
+        let values = Series::new("values".into(), values);
+        col(column_name)
+            .cast(DataType::String)
+            .is_in(lit(values).implode(), false)
+            .fill_null(lit(false))
+    };
+    if include_null {
+        matched.or(col(column_name).is_null())
+    } else {
+        matched
+    }
+}
+
+/// Merges the values selected in the Value Counts window (None for null) into the InList
+/// condition of the column, adding one when there is none.
+pub fn merge_list_selection(
+    filters: &mut Vec<FilterCondition>,
+    column_name: &str,
+    selected: Vec<Option<String>>,
+) {
+    let include_null = selected.contains(&None);
+    let list_values: Vec<String> = selected.into_iter().flatten().collect();
+    match filters
+        .iter_mut()
+        .find(|f| f.column_name == column_name && f.filter_type == FilterType::InList)
+    {
+        Some(filter) => {
+            filter.list_values = list_values;
+            filter.include_null = include_null;
+        }
+        None => {
+            // Replace the blank placeholder condition of the Filter Dialog
+            filters.retain(|f| {
+                !f.filter_value.is_empty() || !f.list_values.is_empty() || f.include_null
+            });
+            filters.push(FilterCondition {
+                column_name: column_name.to_string(),
+                filter_type: FilterType::InList,
+                filter_value: String::new(),
+                list_values,
+                include_null,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> DataFrame {
+        df!(
+            "city" => [Some("Paris"), Some("Rome"), None, Some("Oslo"), Some("Rome")],
+            "code" => [1i64, 2, 3, 4, 5],
+        )
+        .unwrap()
+    }
+
+    fn in_list(values: &[&str], include_null: bool) -> FilterCondition {
+        FilterCondition {
+            filter_type: FilterType::InList,
+            column_name: "city".to_string(),
+            filter_value: String::new(),
+            list_values: values.iter().map(|v| v.to_string()).collect(),
+            include_null,
+        }
+    }
+
+    fn codes(df: &DataFrame) -> Vec<i64> {
+        df.column("code")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    #[test]
+    fn in_list_keeps_listed_values() {
+        let filtered = filter_dataframe(&sample(), &[in_list(&["Rome", "Oslo"], false)]).unwrap();
+        assert_eq!(codes(&filtered), vec![2, 4, 5]);
+    }
+
+    #[test]
+    fn in_list_matches_nulls_when_included() {
+        let filtered = filter_dataframe(&sample(), &[in_list(&["Paris"], true)]).unwrap();
+        assert_eq!(codes(&filtered), vec![1, 3]);
+        let filtered = filter_dataframe(&sample(), &[in_list(&[], true)]).unwrap();
+        assert_eq!(codes(&filtered), vec![3]);
+    }
+
+    #[test]
+    fn empty_in_list_keeps_nothing() {
+        let filtered = filter_dataframe(&sample(), &[in_list(&[], false)]).unwrap();
+        assert_eq!(filtered.height(), 0);
+    }
+
+    #[test]
+    fn in_list_compares_numbers_as_strings() {
+        let mut filter = in_list(&["2", "5"], false);
+        filter.column_name = "code".to_string();
+        let filtered = filter_dataframe(&sample(), &[filter]).unwrap();
+        assert_eq!(codes(&filtered), vec![2, 5]);
+    }
+
+    #[test]
+    fn merge_selection_replaces_placeholder() {
+        let placeholder = FilterCondition {
+            filter_type: FilterType::Equals,
+            column_name: "code".to_string(),
+            filter_value: String::new(),
+            list_values: Vec::new(),
+            include_null: false,
+        };
+        let mut filters = vec![placeholder];
+        merge_list_selection(&mut filters, "city", vec![Some("Rome".to_string()), None]);
+        assert_eq!(filters.len(), 1);
+        assert_eq!(filters[0].filter_type, FilterType::InList);
+        assert_eq!(filters[0].list_values, vec!["Rome".to_string()]);
+        assert!(filters[0].include_null);
+    }
+
+    #[test]
+    fn merge_selection_updates_existing_in_list() {
+        let mut filters = vec![in_list(&["Paris"], true)];
+        merge_list_selection(
+            &mut filters,
+            "city",
+            vec![Some("Oslo".to_string()), Some("Rome".to_string())],
+        );
+        assert_eq!(filters.len(), 1);
+        assert_eq!(
+            filters[0].list_values,
+            vec!["Oslo".to_string(), "Rome".to_string()]
+        );
+        assert!(!filters[0].include_null);
+    }
+}
@@ -0,0 +1,36 @@
+use polars::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct ValueCount {
+    // Value as String (matching the String cast used by Filters), None for Nulls
+    pub value: Option<String>,
+    pub count: usize,
+    pub fraction: f64,
+}
+
+/// Counts the occurrences of every distinct value of the column, most frequent first.
+pub fn value_counts(df: &DataFrame, column_name: &str) -> PolarsResult<Vec<ValueCount>> {
+    let column = df.column(column_name)?.cast(&DataType::String)?;
+    let counts =
+        column
+            .as_materialized_series()
+            .value_counts(true, true, "__parqr_count".into(), false)?;
+
+    let values = counts.columns()[0].str()?;
+    let count_values = counts.column("__parqr_count")?.cast(&DataType::UInt64)?;
+    let count_values = count_values.u64()?;
+    let total = df.height().max(1) as f64;
+
+    Ok(values
+        .into_iter()
+        .zip(count_values)
+        .map(|(value, count)| {
+            let count = count.unwrap_or(0) as usize;
+            ValueCount {
+                value: value.map(|v| v.to_string()),
+                count,
+                fraction: count as f64 / total,
+            }
+        })
+        .collect())
+}
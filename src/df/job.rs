@@ -0,0 +1,32 @@
+use eframe::egui::Context;
+use std::sync::mpsc::{Receiver, TryRecvError, channel};
+use std::thread;
+
+// Computation running on a Background Thread so the UI doesn't stall
+pub struct BackgroundJob<T> {
+    receiver: Receiver<T>,
+}
+
+impl<T: Send + 'static> BackgroundJob<T> {
+    /// Spawns the job, requesting a repaint once the result is ready.
+    pub fn spawn(ctx: &Context, job: impl FnOnce() -> T + Send + 'static) -> Self {
+        let (sender, receiver) = channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = sender.send(job());
+            ctx.request_repaint();
+        });
+        Self { receiver }
+    }
+
+    /// Returns the result if the job has finished, or an error if it panicked.
+    pub fn poll(&self) -> Option<Result<T, String>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(Ok(result)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                Some(Err("Background job stopped unexpectedly".to_string()))
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use polars::prelude::*;
+
+const TOP_VALUE_COUNT: usize = 5;
+const HISTOGRAM_BINS: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct HistogramBin {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+// Numeric Summary (equivalent to Polars describe)
+#[derive(Debug, Clone)]
+pub struct NumericSummary {
+    pub mean: f64,
+    pub std: f64,
+    pub q25: f64,
+    pub median: f64,
+    pub q75: f64,
+    pub histogram: Vec<HistogramBin>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ColumnStats {
+    pub name: String,
+    pub dtype: DataType,
+    pub count: usize,
+    pub null_count: usize,
+    pub distinct_count: Option<usize>,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub numeric: Option<NumericSummary>,
+    pub top_values: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub stats: Result<ColumnStats, String>,
+}
+
+/// Computes the summary statistics for a single column of the DataFrame.
+pub fn column_stats(df: &DataFrame, column_name: &str) -> PolarsResult<ColumnStats> {
+    let column = df.column(column_name)?;
+    let dtype = column.dtype().clone();
+
+    let min = column
+        .min_reduce()
+        .ok()
+        .map(|s| s.value().clone())
+        .filter(|v| !v.is_null())
+        .map(|v| v.to_string());
+    let max = column
+        .max_reduce()
+        .ok()
+        .map(|s| s.value().clone())
+        .filter(|v| !v.is_null())
+        .map(|v| v.to_string());
+
+    let numeric = if dtype.is_primitive_numeric() {
+        numeric_summary(column)?
+    } else {
+        None
+    };
+
+    Ok(ColumnStats {
+        name: column_name.to_string(),
+        dtype,
+        count: column.len(),
+        null_count: column.null_count(),
+        distinct_count: column.n_unique().ok(),
+        min,
+        max,
+        numeric,
+        top_values: top_values(column, TOP_VALUE_COUNT)?,
+    })
+}
+
+/// Computes the summary statistics for every column of the DataFrame.
+///
+/// A column which fails keeps its error, without losing the profile of the others.
+pub fn profile_dataframe(df: &DataFrame) -> Vec<ColumnProfile> {
+    df.get_column_names()
+        .iter()
+        .map(|name| ColumnProfile {
+            name: name.to_string(),
+            stats: column_stats(df, name).map_err(|e| e.to_string()),
+        })
+        .collect()
+}
+
+fn numeric_summary(column: &Column) -> PolarsResult<Option<NumericSummary>> {
+    let values = column.cast(&DataType::Float64)?;
+    let mut values: Vec<f64> = values
+        .f64()?
+        .into_iter()
+        .flatten()
+        .filter(|v| v.is_finite())
+        .collect();
+    if values.is_empty() {
+        return Ok(None);
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = if values.len() > 1 {
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+    } else {
+        0.0
+    };
+
+    Ok(Some(NumericSummary {
+        mean,
+        std: variance.sqrt(),
+        q25: quantile(&values, 0.25),
+        median: quantile(&values, 0.5),
+        q75: quantile(&values, 0.75),
+        histogram: histogram(&values, HISTOGRAM_BINS),
+    }))
+}
+
+// Linear interpolated quantile of sorted values
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let fraction = position - lower as f64;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * fraction
+}
+
+/// Bins sorted values into equal width bins spanning min to max.
+pub fn histogram(sorted: &[f64], bins: usize) -> Vec<HistogramBin> {
+    let (Some(&min), Some(&max)) = (sorted.first(), sorted.last()) else {
+        return Vec::new();
+    };
+    if min == max {
+        return vec![HistogramBin {
+            start: min,
+            end: max,
+            count: sorted.len(),
+        }];
+    }
+
+    let width = (max - min) / bins as f64;
+    let mut counts = vec![0usize; bins];
+    for value in sorted {
+        let bin = (((value - min) / width) as usize).min(bins - 1);
+        counts[bin] += 1;
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| HistogramBin {
+            start: min + width * i as f64,
+            end: min + width * (i + 1) as f64,
+            count,
+        })
+        .collect()
+}
+
+fn top_values(column: &Column, limit: usize) -> PolarsResult<Vec<(String, usize)>> {
+    let counts = column
+        .as_materialized_series()
+        .value_counts(true, true, "__parqr_count".into(), false)?
+        .head(Some(limit));
+
+    let values = &counts.columns()[0];
+    let count_values = counts.column("__parqr_count")?.cast(&DataType::UInt64)?;
+    let count_values = count_values.u64()?;
+
+    Ok((0..counts.height())
+        .map(|i| {
+            let value = values
+                .get(i)
+                .map(|v| v.to_string())
+                .unwrap_or_else(|_| "Error".to_string());
+            (value, count_values.get(i).unwrap_or(0) as usize)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> DataFrame {
+        df!(
+            "value" => [Some(1i64), Some(2), None, Some(3), Some(4)],
+            "name" => [Some("b"), Some("a"), Some("b"), None, Some("b")],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn numeric_column_summary() {
+        let stats = column_stats(&sample(), "value").unwrap();
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.null_count, 1);
+        assert_eq!(stats.distinct_count, Some(5));
+        assert_eq!(stats.min.as_deref(), Some("1"));
+        assert_eq!(stats.max.as_deref(), Some("4"));
+
+        let numeric = stats.numeric.unwrap();
+        assert_eq!(numeric.mean, 2.5);
+        assert!((numeric.std - (5.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(numeric.q25, 1.75);
+        assert_eq!(numeric.median, 2.5);
+        assert_eq!(numeric.q75, 3.25);
+        let counted: usize = numeric.histogram.iter().map(|bin| bin.count).sum();
+        assert_eq!(counted, 4);
+    }
+
+    #[test]
+    fn string_column_top_values() {
+        let stats = column_stats(&sample(), "name").unwrap();
+        assert_eq!(stats.null_count, 1);
+        assert!(stats.numeric.is_none());
+        assert_eq!(stats.top_values[0], ("\"b\"".to_string(), 3));
+        assert_eq!(stats.top_values.len(), 3);
+    }
+
+    #[test]
+    fn quantiles_interpolate() {
+        let sorted = [10.0, 20.0, 30.0];
+        assert_eq!(quantile(&sorted, 0.0), 10.0);
+        assert_eq!(quantile(&sorted, 0.5), 20.0);
+        assert_eq!(quantile(&sorted, 0.75), 25.0);
+        assert_eq!(quantile(&[7.0], 0.25), 7.0);
+    }
+
+    #[test]
+    fn histogram_bins_span_min_to_max() {
+        let bins = histogram(&[0.0, 1.0, 2.0, 3.0, 4.0], 4);
+        assert_eq!(bins.len(), 4);
+        assert_eq!((bins[0].start, bins[3].end), (0.0, 4.0));
+        // The maximum falls in the last bin
+        let counts: Vec<usize> = bins.iter().map(|bin| bin.count).collect();
+        assert_eq!(counts, vec![1, 1, 1, 2]);
+
+        let single = histogram(&[5.0, 5.0], 4);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].count, 2);
+        assert!(histogram(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn profile_keeps_every_column() {
+        let profile = profile_dataframe(&sample());
+        let names: Vec<&str> = profile.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["value", "name"]);
+        assert!(profile.iter().all(|p| p.stats.is_ok()));
+    }
+}
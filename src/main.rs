@@ -1,7 +1,7 @@
 #![windows_subsystem = "windows"]
 use eframe::egui::{
-    self, CentralPanel, Color32, Context, CursorIcon, RichText, ScrollArea, TextStyle,
-    TextWrapMode, Ui, ViewportBuilder,
+    self, CentralPanel, Color32, Context, CursorIcon, Modifiers, Rect, RichText, ScrollArea, Sense,
+    TextStyle, TextWrapMode, Ui, Vec2, ViewportBuilder, ViewportCommand,
 };
 use egui::widgets::Label;
 use egui_extras::{Column, TableBuilder, TableRow};
@@ -10,36 +10,131 @@ use polars::prelude::ParquetWriter;
 use polars::prelude::*;
 use polars_buffer::Buffer;
 use rfd::FileDialog;
+use std::collections::{BTreeSet, HashMap};
 use std::env;
 use std::path::PathBuf;
-use walkers::{HttpTiles, MapMemory, Position, sources::OpenStreetMap};
+use std::time::{Duration, Instant};
+use walkers::{MapMemory, Position};
 
 mod ui {
+    pub mod frequency;
+    pub mod palette;
+    pub mod profile;
     pub mod views;
 }
-use crate::ui::views::ViewTab;
+use crate::ui::{
+    frequency::FrequencyView,
+    profile::{render_column_stats, render_profile},
+    views::ViewTab,
+};
 
 mod df {
+    pub mod aggregate;
+    pub mod computed;
     pub mod filetype;
     pub mod filter;
+    pub mod frequency;
+    pub mod job;
     pub mod sort;
+    pub mod stats;
+}
+use crate::df::{
+    aggregate::{AggFunction, Aggregation, GroupBySpec, aggregate_dataframe},
+    computed::{ComputedColumn, add_computed_columns, dependent_columns, validate_computed_column},
+    filetype::FileType,
+    filter::{FilterCondition, FilterType, merge_list_selection},
+    frequency::value_counts,
+    job::BackgroundJob,
+    sort::SortCondition,
+    stats::{ColumnProfile, ColumnStats, column_stats, profile_dataframe},
+};
+
+mod chart {
+    pub mod data;
+    pub mod plot;
+    pub mod svg;
 }
-use crate::df::{filetype::FileType, filter::FilterType, sort::SortCondition};
+use crate::chart::{
+    data::{ChartConfig, ChartData, ChartType, build_chart, numeric_values},
+    plot::render_chart,
+    svg::chart_to_svg,
+};
 
 mod table {
     pub mod table;
 }
-use crate::table::table::render_table_body;
+use crate::table::table::{render_table_body, row_values};
+
+mod config {
+    pub mod store;
+}
 
 mod map {
+    pub mod binning;
+    pub mod bounds;
+    pub mod cluster;
+    pub mod colormap;
+    pub mod columns;
+    pub mod extract;
+    pub mod geometry;
+    pub mod h3tools;
+    pub mod heatmap;
     pub mod hexagon;
+    pub mod interaction;
     pub mod marker;
+    pub mod measure;
+    pub mod shape;
+    pub mod spatial;
+    pub mod tilecache;
+    pub mod tiles;
+    pub mod timeline;
+    pub mod trajectory;
 }
-use crate::map::{hexagon::HexagonPlot, marker::PointPlot};
+use crate::map::{
+    binning::{H3Binning, H3Bins, bin_points},
+    bounds::Bounds,
+    cluster::PointIndex,
+    colormap::{ColorBy, Colormap, Legend, LegendPlot, color_rows},
+    columns::{MapColumns, detect_map_columns, load_map_columns, save_map_columns, schema_key},
+    extract::{Extracted, geometry_rows, h3_cell_rows, lat_lon_rows},
+    geometry::{Geometry, geoparquet_columns},
+    h3tools::{H3Operation, H3View, apply_h3_operation, k_ring, output_column_names},
+    heatmap::{HeatmapCache, HeatmapPlot, HeatmapStyle},
+    hexagon::{HexagonPlot, HexagonStyle},
+    interaction::{HoveredFeature, PointerPosition, SelectionPlot, SelectionTool, ViewportBounds},
+    marker::{MarkerShape, MarkerStyle, PointPlot, marker_radii, marker_shapes, shape_mapping},
+    measure::{
+        MeasurePlot, MeasureTool, format_position, measurement_summary, measurement_wkt,
+        position_cell,
+    },
+    shape::{GeometryPlot, GeometryStyle},
+    spatial::{SpatialFilter, cell_center, rows_in_polygon, spatial_filter_dataframe},
+    tilecache::clear_tile_cache,
+    tiles::{MapTiles, TileProvider, TileSettings, create_tiles, create_tiles_or_default},
+    timeline::{RowTimes, Timeline, WindowFeatures, detect_time_column, format_time, row_times},
+    trajectory::{Track, TrajectoryPlot, TrajectoryStyle, build_tracks, seconds_per_unit},
+};
+
+// Style of the K-Ring around a clicked Hexagon
+const K_RING_STYLE: HexagonStyle = HexagonStyle {
+    fill_color: Color32::from_rgb(255, 140, 0),
+    fill_opacity: 0.3,
+    stroke_color: Color32::from_rgb(255, 140, 0),
+    stroke_width: 2.0,
+};
 
 // Feature flag to control row index column visibility
 const SHOW_ROW_INDEX: bool = false;
 
+// Columns shown in Map Tooltips until others are chosen
+const MAX_TOOLTIP_COLUMNS: usize = 10;
+
+// Pause in panning/zooming before the Table is filtered to the Map View
+const VIEWPORT_FILTER_DELAY: Duration = Duration::from_millis(300);
+
+// Pause between Timeline steps while playing
+const PLAYBACK_INTERVAL: Duration = Duration::from_millis(200);
+
 struct Parqr {
     dataframe: Option<DataFrame>,
     original_dataframe: Option<DataFrame>,
@@ -55,10 +150,119 @@ struct Parqr {
 
     sort_condition: Option<SortCondition>,
 
-    tiles: HttpTiles,
+    computed_dialog_open: bool,
+    computed_columns: Vec<ComputedColumn>,
+    computed_name: String,
+    computed_expression: String,
+    computed_result: Option<Result<String, String>>,
+
+    group_by_dialog_open: bool,
+    group_by_spec: GroupBySpec,
+    active_group_by: Option<GroupBySpec>,
+
+    h3_tools_open: bool,
+    h3_tool_column: String,
+    h3_operation: H3Operation,
+    h3_tool_resolution: u8,
+    h3_tool_result: Option<Result<String, String>>,
+    // Compacted or uncompacted cells shown instead of the filtered rows
+    active_h3_view: Option<H3View>,
+
+    // Header tooltip statistics, computed in the background on first hover
+    column_stats: HashMap<String, Result<ColumnStats, String>>,
+    column_stats_jobs: HashMap<String, BackgroundJob<Result<ColumnStats, String>>>,
+    profile: Option<Vec<ColumnProfile>>,
+    profile_job: Option<BackgroundJob<Vec<ColumnProfile>>>,
+
+    frequency_view: Option<FrequencyView>,
+
+    chart_config: ChartConfig,
+    chart_data: Option<Result<ChartData, String>>,
+    chart_rect: Option<Rect>,
+    chart_png_path: Option<PathBuf>,
+    chart_export_result: Option<String>,
+
+    tiles: MapTiles,
+    tile_settings: TileSettings,
+    tile_error: Option<String>,
+    tile_precache_zoom: u8,
+    // (south west, north east) of the last drawn Map
+    map_viewport: Option<(Position, Position)>,
     map_memory: MapMemory,
+    map_columns: MapColumns,
+    map_warnings: Vec<String>,
     positions: Vec<Position>,
+    position_rows: Vec<usize>,
+    point_index: PointIndex,
+    show_markers: bool,
+    marker_style: MarkerStyle,
+    marker_radii: Vec<f32>,
+    marker_shapes: Vec<MarkerShape>,
+    show_heatmap: bool,
+    heatmap_style: HeatmapStyle,
+    // Per Row Heatmap Weights (empty without a weight column)
+    heatmap_weights: Vec<f64>,
+    heatmap_cache: HeatmapCache,
+    show_h3_bins: bool,
+    h3_binning: H3Binning,
+    h3_bins: Option<H3Bins>,
+    // Binning running on a Background Thread, the previous bins are shown until it finishes
+    h3_bins_job: Option<BackgroundJob<Result<H3Bins, String>>>,
+    show_tracks: bool,
+    trajectory_style: TrajectoryStyle,
+    tracks: Vec<Track>,
+    timeline: Timeline,
+    // Times of the rows in the Timeline column
+    row_times: Option<RowTimes>,
+    // Points and H3 cells within the Time Window (None draws all)
+    time_window: Option<WindowFeatures>,
+    last_time_step: Option<Instant>,
     h3cells: Vec<String>,
+    h3cell_rows: Vec<usize>,
+    // H3 cells drawn as markers at their centroids
+    h3_centroid_markers: bool,
+    h3_centroids: Vec<Position>,
+    h3_centroid_index: PointIndex,
+    hexagon_style: HexagonStyle,
+    geometries: Vec<Geometry>,
+    geometry_rows: Vec<usize>,
+    geometry_style: GeometryStyle,
+    // Geometry columns declared in the GeoParquet metadata of the loaded file
+    geoparquet_columns: Vec<String>,
+    // Neighbours within k steps of the clicked Hexagon (None hides them)
+    k_ring_size: Option<u32>,
+    k_ring_cells: Vec<String>,
+    map_color_by: Option<ColorBy>,
+    map_colors: Vec<Color32>,
+    map_legend: Option<Legend>,
+    // Columns shown in Map Tooltips (None shows the first columns)
+    map_tooltip_columns: Option<Vec<String>>,
+
+    selected_rows: BTreeSet<usize>,
+    // Row a Shift+Click range selection starts from
+    selection_anchor: Option<usize>,
+    // Row shown in the Record Window
+    inspected_row: Option<usize>,
+    selection_tool: SelectionTool,
+    // Area being drawn on the Map
+    selection_path: Vec<Position>,
+    measure_tool: MeasureTool,
+    measure_points: Vec<Position>,
+    // Map position under the pointer, and where the Map was right-clicked
+    map_cursor: Option<Position>,
+    map_context_position: Option<Position>,
+    // Resolution of the H3 cell shown for the pointer
+    cursor_h3_resolution: Option<u8>,
+    // Last Area drawn on the Map
+    map_selection: Option<Vec<Position>>,
+    spatial_filter: Option<SpatialFilter>,
+    // Bounds the Map is fitted to when next shown
+    map_fit_bounds: Option<Bounds>,
+    viewport_filter_enabled: bool,
+    viewport_filter: Option<SpatialFilter>,
+    // Viewport waiting for the map to settle before it is applied
+    pending_viewport_filter: Option<(SpatialFilter, Instant)>,
+    table_scroll_to_row: Option<usize>,
 
     export_file_path: Option<PathBuf>,
     export_file_type: FileType,
@@ -68,6 +272,11 @@ struct Parqr {
 
 impl Parqr {
     fn new(files_to_load: Vec<PathBuf>, ctx: Context) -> Self {
+        // Falls back to OpenStreetMap when the saved basemap cannot be opened
+        let tile_settings = TileSettings::load();
+        let (tiles, tile_error) = create_tiles_or_default(&tile_settings, ctx);
+        let tile_error = tile_error.map(|e| format!("Basemap error: {}", e));
+
         Self {
             dataframe: None,
             original_dataframe: None,
@@ -83,10 +292,105 @@ impl Parqr {
 
             sort_condition: None,
 
-            tiles: HttpTiles::new(OpenStreetMap, ctx),
+            computed_dialog_open: false,
+            computed_columns: Vec::new(),
+            computed_name: String::new(),
+            computed_expression: String::new(),
+            computed_result: None,
+
+            group_by_dialog_open: false,
+            group_by_spec: GroupBySpec::default(),
+            active_group_by: None,
+
+            h3_tools_open: false,
+            h3_tool_column: String::new(),
+            h3_operation: H3Operation::Parent,
+            h3_tool_resolution: 5,
+            h3_tool_result: None,
+            active_h3_view: None,
+
+            column_stats: HashMap::new(),
+            column_stats_jobs: HashMap::new(),
+            profile: None,
+            profile_job: None,
+
+            frequency_view: None,
+
+            chart_config: ChartConfig {
+                chart_type: ChartType::Histogram,
+                x_column: String::new(),
+                y_column: None,
+                color_column: None,
+            },
+            chart_data: None,
+            chart_rect: None,
+            chart_png_path: None,
+            chart_export_result: None,
+
+            tiles,
+            tile_settings,
+            tile_error,
+            tile_precache_zoom: 14,
+            map_viewport: None,
             map_memory: MapMemory::default(),
+            map_columns: MapColumns::default(),
+            map_warnings: Vec::new(),
             positions: Vec::new(),
+            position_rows: Vec::new(),
+            point_index: PointIndex::default(),
+            show_markers: true,
+            marker_style: MarkerStyle::default(),
+            marker_radii: Vec::new(),
+            marker_shapes: Vec::new(),
+            show_heatmap: false,
+            heatmap_style: HeatmapStyle::default(),
+            heatmap_weights: Vec::new(),
+            heatmap_cache: HeatmapCache::default(),
+            show_h3_bins: false,
+            h3_binning: H3Binning::default(),
+            h3_bins: None,
+            h3_bins_job: None,
+            show_tracks: false,
+            trajectory_style: TrajectoryStyle::default(),
+            tracks: Vec::new(),
+            timeline: Timeline::default(),
+            row_times: None,
+            time_window: None,
+            last_time_step: None,
             h3cells: Vec::new(),
+            h3cell_rows: Vec::new(),
+            h3_centroid_markers: false,
+            h3_centroids: Vec::new(),
+            h3_centroid_index: PointIndex::default(),
+            hexagon_style: HexagonStyle::default(),
+            geometries: Vec::new(),
+            geometry_rows: Vec::new(),
+            geometry_style: GeometryStyle::default(),
+            geoparquet_columns: Vec::new(),
+            k_ring_size: None,
+            k_ring_cells: Vec::new(),
+            map_color_by: None,
+            map_colors: Vec::new(),
+            map_legend: None,
+            map_tooltip_columns: None,
+
+            selected_rows: BTreeSet::new(),
+            selection_anchor: None,
+            inspected_row: None,
+            selection_tool: SelectionTool::default(),
+            selection_path: Vec::new(),
+            measure_tool: MeasureTool::default(),
+            measure_points: Vec::new(),
+            map_cursor: None,
+            map_context_position: None,
+            cursor_h3_resolution: None,
+            map_selection: None,
+            spatial_filter: None,
+            map_fit_bounds: None,
+            viewport_filter_enabled: false,
+            viewport_filter: None,
+            pending_viewport_filter: None,
+            table_scroll_to_row: None,
 
             export_file_path: None,
             export_file_type: FileType::Csv,
@@ -109,6 +413,12 @@ impl Parqr {
                 .and_then(FileType::from_extension)
         });
 
+        // GeoParquet files declare their geometry columns in the file metadata
+        self.geoparquet_columns = match (&file_type, paths.first()) {
+            (Some(FileType::Parquet) | None, Some(path)) => geoparquet_columns(path),
+            _ => Vec::new(),
+        };
+
         let pl_paths: Vec<PlRefPath> = paths
             .into_iter()
             .map(|pb| PlRefPath::new(Arc::<str>::from(pb.to_string_lossy().into_owned())))
@@ -143,6 +453,7 @@ impl Parqr {
             }
         };
         self.error_message = None;
+        let df = self.apply_computed_columns(df);
 
         self.column_names = df
             .get_column_names()
@@ -152,11 +463,14 @@ impl Parqr {
         self.original_dataframe = Some(df.clone());
         self.dataframe = Some(df);
         self.filter_conditions = Vec::new();
-
-        // Reset to Table Tab if DataFrame not Mapable
-        if matches!(self.selected_tab, ViewTab::Map) && !self.has_mappable_columns() {
-            self.selected_tab = ViewTab::Table;
-        }
+        self.spatial_filter = None;
+        self.viewport_filter = None;
+        self.group_by_spec = GroupBySpec::default();
+        self.active_group_by = None;
+        self.active_h3_view = None;
+        self.invalidate_derived_data();
+
+        self.resolve_map_columns();
         self.render_map_data();
     }
 
@@ -190,6 +504,15 @@ impl Parqr {
                 if ui.button("Filter").clicked() {
                     self.filter_dialog_open = true;
                 }
+                if ui.button("Add Column").clicked() {
+                    self.computed_dialog_open = true;
+                }
+                if ui.button("Group By").clicked() {
+                    self.group_by_dialog_open = true;
+                }
+                if ui.button("H3 Tools").clicked() {
+                    self.h3_tools_open = true;
+                }
             });
         });
     }
@@ -218,14 +541,19 @@ impl Parqr {
 
     fn apply_sort(&mut self) {
         if let (Some(df), Some(sort_cond)) = (&self.dataframe, &self.sort_condition) {
-            let column = PlSmallStr::from(&sort_cond.column_name);
+            let sorted = df.column(&sort_cond.column_name).and_then(|column| {
+                let order = column
+                    .arg_sort(SortOptions::default().with_order_descending(!sort_cond.ascending));
+                df.take(&order).map(|df| (df, order))
+            });
 
-            match df.sort(
-                vec![column],
-                SortMultipleOptions::new().with_order_descending(!sort_cond.ascending),
-            ) {
-                Ok(sorted_df) => {
+            match sorted {
+                Ok((sorted_df, order)) => {
                     self.dataframe = Some(sorted_df);
+                    // Rows moved, so the selection is cleared and the map features follow them
+                    self.clear_selection();
+                    let order: Vec<usize> = order.into_no_null_iter().map(|i| i as usize).collect();
+                    self.reorder_map_rows(&order);
                 }
                 Err(e) => {
                     self.error_message = Some(format!("Sort error: {}", e));
@@ -234,6 +562,37 @@ impl Parqr {
         }
     }
 
+    // Points the map features at the rows' new positions, without rebuilding or refitting
+    // the layers (`order` holds the previous row of every sorted row)
+    fn reorder_map_rows(&mut self, order: &[usize]) {
+        let mut new_rows = vec![0; order.len()];
+        for (new_row, &old_row) in order.iter().enumerate() {
+            new_rows[old_row] = new_row;
+        }
+        for rows in [
+            &mut self.position_rows,
+            &mut self.h3cell_rows,
+            &mut self.geometry_rows,
+        ] {
+            for row in rows.iter_mut() {
+                *row = new_rows[*row];
+            }
+        }
+
+        permute_rows(&mut self.map_colors, order);
+        permute_rows(&mut self.marker_radii, order);
+        permute_rows(&mut self.marker_shapes, order);
+        permute_rows(&mut self.heatmap_weights, order);
+        if let Some(row_times) = &mut self.row_times {
+            permute_rows(&mut row_times.times, order);
+        }
+        self.update_time_window();
+        // Tracks without an order column follow the row order
+        if self.trajectory_style.order_column.is_none() {
+            self.construct_tracks();
+        }
+    }
+
     fn apply_filter(&mut self) {
         if let Some(original_df) = &self.original_dataframe {
             if self.filter_conditions.is_empty() {
@@ -252,14 +611,527 @@ impl Parqr {
                 }
             }
         }
+        self.apply_spatial_filter();
+        self.apply_h3_view();
+        self.apply_group_by();
+        self.invalidate_derived_data();
         self.render_map_data();
     }
 
+    // Drawn area and map viewport filters
+    fn apply_spatial_filter(&mut self) {
+        for spatial_filter in [&self.spatial_filter, &self.viewport_filter]
+            .into_iter()
+            .flatten()
+        {
+            let Some(df) = &self.dataframe else {
+                return;
+            };
+            match spatial_filter_dataframe(df, spatial_filter) {
+                Ok(filtered_df) => {
+                    self.dataframe = Some(filtered_df);
+                }
+                Err(e) => {
+                    self.error_message = Some(format!("Spatial filter error: {}", e));
+                }
+            }
+        }
+    }
+
+    // Views which cannot be applied (anymore) are dropped, showing the filtered rows
+    fn apply_h3_view(&mut self) {
+        if let (Some(df), Some(view)) = (&self.dataframe, &self.active_h3_view) {
+            match apply_h3_operation(df, &view.column_name, view.operation, view.resolution) {
+                Ok(view_df) => {
+                    self.dataframe = Some(view_df);
+                }
+                Err(e) => {
+                    let message = format!("H3 {} error: {}", view.operation, e);
+                    self.h3_tool_result = Some(Err(message.clone()));
+                    self.error_message = Some(message);
+                    self.active_h3_view = None;
+                }
+            }
+        }
+    }
+
+    fn apply_group_by(&mut self) {
+        if let (Some(df), Some(spec)) = (&self.dataframe, &self.active_group_by) {
+            match aggregate_dataframe(df, spec) {
+                Ok(aggregated_df) => {
+                    self.dataframe = Some(aggregated_df);
+                }
+                Err(e) => {
+                    self.error_message = Some(format!("Group by error: {}", e));
+                }
+            }
+        }
+        self.update_column_names();
+    }
+
+    fn update_column_names(&mut self) {
+        let column_names: Vec<String> = self
+            .dataframe
+            .as_ref()
+            .map(|df| {
+                df.get_column_names()
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if column_names != self.column_names {
+            self.column_names = column_names;
+            // Reset export columns selection when the columns change
+            self.export_selected_columns = None;
+            self.map_tooltip_columns = None;
+            self.resolve_map_columns();
+        }
+    }
+
+    // Columns of the loaded data (before any Group By)
+    fn source_column_names(&self) -> Vec<String> {
+        self.original_dataframe
+            .as_ref()
+            .map(|df| {
+                df.get_column_names()
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // Re-applies the session's Computed Columns which are valid for the loaded schema
+    fn apply_computed_columns(&mut self, mut df: DataFrame) -> DataFrame {
+        let mut skipped = Vec::new();
+        for column in &self.computed_columns {
+            let extended = validate_computed_column(&df, column)
+                .and_then(|_| add_computed_columns(&df, std::slice::from_ref(column)));
+            match extended {
+                Ok(extended_df) => df = extended_df,
+                Err(_) => skipped.push(column.name.clone()),
+            }
+        }
+        if !skipped.is_empty() {
+            self.error_message = Some(format!(
+                "Computed columns not valid for this data: {}",
+                skipped.join(", ")
+            ));
+        }
+        df
+    }
+
+    fn add_computed_column(&mut self) {
+        let Some(original_df) = &self.original_dataframe else {
+            return;
+        };
+        let column = ComputedColumn {
+            name: self.computed_name.trim().to_string(),
+            expression: self.computed_expression.clone(),
+        };
+
+        let extended = validate_computed_column(original_df, &column).and_then(|dtype| {
+            add_computed_columns(original_df, std::slice::from_ref(&column)).map(|df| (df, dtype))
+        });
+        match extended {
+            Ok((extended_df, dtype)) => {
+                self.computed_result = Some(Ok(format!("Added {} ({})", column.name, dtype)));
+                self.original_dataframe = Some(extended_df);
+                self.computed_columns.push(column);
+                self.computed_name.clear();
+                self.computed_expression.clear();
+                self.apply_filter();
+            }
+            Err(e) => self.computed_result = Some(Err(e.to_string())),
+        }
+    }
+
+    // Columns used by other Computed Columns are kept, so those stay valid
+    fn remove_computed_column(&mut self, index: usize) {
+        let name = &self.computed_columns[index].name;
+        let dependents = dependent_columns(&self.computed_columns, name);
+        if !dependents.is_empty() {
+            self.computed_result = Some(Err(format!(
+                "Cannot remove {}, it is used by {}",
+                name,
+                dependents.join(", ")
+            )));
+            return;
+        }
+
+        let column = self.computed_columns.remove(index);
+        if let Some(original_df) = &self.original_dataframe
+            && let Ok(df) = original_df.drop(&column.name)
+        {
+            self.original_dataframe = Some(df);
+        }
+        self.apply_filter();
+    }
+
+    fn render_computed_dialog(&mut self, ui: &mut Ui) {
+        if !self.computed_dialog_open {
+            return;
+        }
+
+        let mut add = false;
+        let mut remove_index = None;
+
+        egui::Window::new("Add Column")
+            .open(&mut self.computed_dialog_open)
+            .auto_sized()
+            .collapsible(false)
+            .show(ui.ctx(), |ui| {
+                egui::Grid::new("computed_column_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Name:");
+                        ui.text_edit_singleline(&mut self.computed_name);
+                        ui.end_row();
+                        ui.label("Expression:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.computed_expression)
+                                .hint_text("price * qty")
+                                .desired_width(320.0),
+                        );
+                        ui.end_row();
+                    });
+                ui.label(
+                    RichText::new("SQL expression syntax, e.g. concat(first, ' ', last)").weak(),
+                );
+
+                if ui
+                    .add_enabled(
+                        !self.computed_name.trim().is_empty()
+                            && !self.computed_expression.trim().is_empty(),
+                        egui::Button::new("Add"),
+                    )
+                    .clicked()
+                {
+                    add = true;
+                }
+
+                match &self.computed_result {
+                    Some(Ok(msg)) => {
+                        ui.label(msg);
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(Color32::RED, e);
+                    }
+                    None => {}
+                }
+
+                if !self.computed_columns.is_empty() {
+                    ui.separator();
+                    ui.label("Computed columns:");
+                    for (i, column) in self.computed_columns.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{} = {}", column.name, column.expression));
+                            if ui.button("Remove").clicked() {
+                                remove_index = Some(i);
+                            }
+                        });
+                    }
+                }
+            });
+
+        if add {
+            self.add_computed_column();
+        }
+        if let Some(i) = remove_index {
+            self.remove_computed_column(i);
+        }
+    }
+
+    fn render_group_by_dialog(&mut self, ui: &mut Ui) {
+        if !self.group_by_dialog_open {
+            return;
+        }
+
+        let source_columns = self.source_column_names();
+        let mut apply = false;
+        let mut clear = false;
+        let mut remove_indices = Vec::new();
+        let spec = &mut self.group_by_spec;
+
+        egui::Window::new("Group By")
+            .open(&mut self.group_by_dialog_open)
+            .auto_sized()
+            .collapsible(false)
+            .show(ui.ctx(), |ui| {
+                ui.label("Group by keys:");
+                egui::ScrollArea::vertical()
+                    .id_salt("group_by_keys")
+                    .max_height(120.0)
+                    .show(ui, |ui| {
+                        for col in &source_columns {
+                            let mut checked = spec.keys.contains(col);
+                            if ui.checkbox(&mut checked, col).changed() {
+                                if checked {
+                                    spec.keys.push(col.clone());
+                                } else {
+                                    spec.keys.retain(|k| k != col);
+                                }
+                            }
+                        }
+                    });
+
+                ui.separator();
+                ui.label("Aggregations:");
+                for (i, agg) in spec.aggregations.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt(format!("group_by_column_{}", i))
+                            .selected_text(&agg.column_name)
+                            .show_ui(ui, |ui| {
+                                for col in &source_columns {
+                                    ui.selectable_value(&mut agg.column_name, col.clone(), col);
+                                }
+                            });
+                        egui::ComboBox::from_id_salt(format!("group_by_function_{}", i))
+                            .selected_text(agg.function.to_string())
+                            .show_ui(ui, |ui| {
+                                for function in AggFunction::ALL {
+                                    ui.selectable_value(
+                                        &mut agg.function,
+                                        function,
+                                        function.to_string(),
+                                    );
+                                }
+                            });
+                        if ui.button("Remove").clicked() {
+                            remove_indices.push(i);
+                        }
+                    });
+                }
+                if ui.button("Add Aggregation").clicked() {
+                    spec.aggregations.push(Aggregation {
+                        column_name: source_columns.first().cloned().unwrap_or_default(),
+                        function: AggFunction::Count,
+                    });
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Pivot column:");
+                    egui::ComboBox::from_id_salt("group_by_pivot")
+                        .selected_text(spec.pivot_column.as_deref().unwrap_or("None"))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut spec.pivot_column, None, "None");
+                            for col in &source_columns {
+                                ui.selectable_value(&mut spec.pivot_column, Some(col.clone()), col);
+                            }
+                        });
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!spec.aggregations.is_empty(), egui::Button::new("Apply"))
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                    if ui.button("Clear").clicked() {
+                        clear = true;
+                    }
+                });
+            });
+
+        for &i in remove_indices.iter().rev() {
+            self.group_by_spec.aggregations.remove(i);
+        }
+
+        if apply {
+            self.active_group_by = Some(self.group_by_spec.clone());
+            self.apply_filter();
+        } else if clear {
+            self.group_by_spec = GroupBySpec::default();
+            self.active_group_by = None;
+            self.apply_filter();
+        }
+    }
+
+    // Compact and Uncompact show their result as a view of the filtered rows, while the
+    // other operations add columns to the loaded data
+    fn apply_h3_tool(&mut self) {
+        let operation = self.h3_operation;
+        if operation.changes_rows() {
+            self.active_h3_view = Some(H3View {
+                column_name: self.h3_tool_column.clone(),
+                operation,
+                resolution: self.h3_tool_resolution,
+            });
+            self.apply_filter();
+            if self.active_h3_view.is_some() {
+                let rows = self.dataframe.as_ref().map_or(0, |df| df.height());
+                self.h3_tool_result = Some(Ok(format!(
+                    "{}: showing {} rows of the filtered data",
+                    operation, rows
+                )));
+            }
+            return;
+        }
+
+        let Some(original_df) = &self.original_dataframe else {
+            return;
+        };
+        let result = apply_h3_operation(
+            original_df,
+            &self.h3_tool_column,
+            operation,
+            self.h3_tool_resolution,
+        );
+        match result {
+            Ok(df) => {
+                let names =
+                    output_column_names(&self.h3_tool_column, operation, self.h3_tool_resolution);
+                self.h3_tool_result =
+                    Some(Ok(format!("{}: added {}", operation, names.join(", "))));
+                self.original_dataframe = Some(df);
+                self.apply_filter();
+            }
+            Err(e) => self.h3_tool_result = Some(Err(e.to_string())),
+        }
+    }
+
+    fn render_h3_tools_dialog(&mut self, ui: &mut Ui) {
+        if !self.h3_tools_open {
+            return;
+        }
+
+        let source_columns = self.source_column_names();
+        if !source_columns.contains(&self.h3_tool_column) {
+            self.h3_tool_column = self
+                .map_columns
+                .h3cell
+                .clone()
+                .filter(|col| source_columns.contains(col))
+                .or_else(|| source_columns.first().cloned())
+                .unwrap_or_default();
+        }
+        let mut apply = false;
+        let mut clear_view = false;
+
+        egui::Window::new("H3 Tools")
+            .open(&mut self.h3_tools_open)
+            .auto_sized()
+            .collapsible(false)
+            .show(ui.ctx(), |ui| {
+                egui::Grid::new("h3_tools_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("H3 column:");
+                        egui::ComboBox::from_id_salt("h3_tool_column")
+                            .selected_text(&self.h3_tool_column)
+                            .show_ui(ui, |ui| {
+                                for col in &source_columns {
+                                    ui.selectable_value(&mut self.h3_tool_column, col.clone(), col);
+                                }
+                            });
+                        ui.end_row();
+                        ui.label("Operation:");
+                        egui::ComboBox::from_id_salt("h3_tool_operation")
+                            .selected_text(self.h3_operation.to_string())
+                            .show_ui(ui, |ui| {
+                                for operation in H3Operation::ALL {
+                                    ui.selectable_value(
+                                        &mut self.h3_operation,
+                                        operation,
+                                        operation.to_string(),
+                                    );
+                                }
+                            });
+                        ui.end_row();
+                        if self.h3_operation.uses_resolution() {
+                            ui.label("Resolution:");
+                            ui.add(egui::Slider::new(&mut self.h3_tool_resolution, 0..=15));
+                            ui.end_row();
+                        }
+                    });
+                ui.label(RichText::new(self.h3_operation.description()).weak());
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!self.h3_tool_column.is_empty(), egui::Button::new("Apply"))
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                    if let Some(view) = &self.active_h3_view
+                        && ui
+                            .button(format!("Clear {} View", view.operation))
+                            .clicked()
+                    {
+                        clear_view = true;
+                    }
+                });
+
+                match &self.h3_tool_result {
+                    Some(Ok(msg)) => {
+                        ui.label(msg);
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(Color32::RED, e);
+                    }
+                    None => {}
+                }
+            });
+
+        if apply {
+            self.apply_h3_tool();
+        }
+        if clear_view {
+            self.active_h3_view = None;
+            self.h3_tool_result = None;
+            self.apply_filter();
+        }
+    }
+
+    // InList values, removable one by one, with an entry adding a value on Enter
+    fn render_list_values(ui: &mut Ui, filter: &mut FilterCondition) -> bool {
+        let mut changed = false;
+        let count = filter.list_values.len() + filter.include_null as usize;
+        ui.menu_button(format!("{} values", count), |ui| {
+            let mut removed = None;
+            for (i, value) in filter.list_values.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.small_button("✖").clicked() {
+                        removed = Some(i);
+                    }
+                    ui.label(value);
+                });
+            }
+            if let Some(i) = removed {
+                filter.list_values.remove(i);
+                changed = true;
+            }
+            changed |= ui.checkbox(&mut filter.include_null, "null").changed();
+        });
+
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut filter.filter_value)
+                .hint_text("Add value")
+                .desired_width(120.0),
+        );
+        if response.lost_focus()
+            && ui.input(|i| i.key_pressed(egui::Key::Enter))
+            && !filter.filter_value.is_empty()
+        {
+            let value = std::mem::take(&mut filter.filter_value);
+            if !filter.list_values.contains(&value) {
+                filter.list_values.push(value);
+                changed = true;
+            }
+        }
+        changed
+    }
+
     fn render_filter_dialog(&mut self, ui: &mut egui::Ui) {
         let mut apply_filter = false;
         let mut add_filter = false;
         let mut remove_indices = Vec::new();
         let filter_len = self.filter_conditions.len();
+        let source_columns = self.source_column_names();
 
         if self.filter_dialog_open {
             egui::Window::new("Filter")
@@ -276,7 +1148,7 @@ impl Parqr {
                                         .selected_text(&filter.column_name)
                                         .show_ui(ui, |ui| {
                                             let mut changed = false;
-                                            for col in &self.column_names {
+                                            for col in &source_columns {
                                                 if ui
                                                     .selectable_value(
                                                         &mut filter.column_name,
@@ -319,13 +1191,26 @@ impl Parqr {
                                             {
                                                 changed = true;
                                             }
+                                            if ui
+                                                .selectable_value(
+                                                    &mut filter.filter_type,
+                                                    FilterType::InList,
+                                                    FilterType::InList.to_string(),
+                                                )
+                                                .changed()
+                                            {
+                                                changed = true;
+                                            }
                                             changed
                                         })
                                         .inner
                                         .unwrap_or(false);
 
-                                let value_changed =
-                                    ui.text_edit_singleline(&mut filter.filter_value).changed();
+                                let value_changed = if filter.filter_type == FilterType::InList {
+                                    Self::render_list_values(ui, filter)
+                                } else {
+                                    ui.text_edit_singleline(&mut filter.filter_value).changed()
+                                };
 
                                 if filter_len > 1 {
                                     if ui.button("Remove").clicked() {
@@ -353,18 +1238,22 @@ impl Parqr {
 
         if add_filter {
             self.filter_conditions.push(df::filter::FilterCondition {
-                column_name: self.column_names.get(0).cloned().unwrap_or_default(),
+                column_name: source_columns.first().cloned().unwrap_or_default(),
                 filter_type: df::filter::FilterType::Equals,
                 filter_value: String::new(),
+                list_values: Vec::new(),
+                include_null: false,
             });
             apply_filter = true;
         }
 
         if self.filter_conditions.is_empty() {
             self.filter_conditions.push(df::filter::FilterCondition {
-                column_name: self.column_names.get(0).cloned().unwrap_or_default(),
+                column_name: source_columns.first().cloned().unwrap_or_default(),
                 filter_type: df::filter::FilterType::Contains,
                 filter_value: String::new(),
+                list_values: Vec::new(),
+                include_null: false,
             });
             apply_filter = true;
         }
@@ -375,100 +1264,1878 @@ impl Parqr {
     }
 
     fn find_lat_lon_columns(&self) -> Option<(String, String)> {
-        let lat_candidates = ["latitude", "lat"];
-        let lon_candidates = ["longitude", "lon", "lng"];
-
-        let mut lat_col = None;
-        let mut lon_col = None;
-
-        for col in &self.column_names {
-            let col_lower = col.to_lowercase();
-            if lat_candidates.iter().any(|&name| col_lower == name) {
-                lat_col = Some(col.clone());
-            }
-            if lon_candidates.iter().any(|&name| col_lower == name) {
-                lon_col = Some(col.clone());
-            }
-        }
-
-        match (lat_col, lon_col) {
-            (Some(lat), Some(lon)) => Some((lat, lon)),
-            _ => None,
-        }
+        self.map_columns.lat_lon()
     }
 
-    fn extract_lat_lons(&self) -> Option<(Vec<f64>, Vec<f64>)> {
+    fn extract_lat_lons(&mut self) -> Option<Extracted<(f64, f64)>> {
         let df = self.dataframe.as_ref()?;
         let (lat_col, lon_col) = self.find_lat_lon_columns()?;
 
-        let lat_series = df.column(&lat_col).ok()?;
-        let lon_series = df.column(&lon_col).ok()?;
-
-        let lat_values: Vec<f64> = lat_series.f64().ok()?.into_no_null_iter().collect();
-        let lon_values: Vec<f64> = lon_series.f64().ok()?.into_no_null_iter().collect();
-
-        Some((lat_values, lon_values))
+        match lat_lon_rows(df, &lat_col, &lon_col) {
+            Ok(extracted) => Some(extracted),
+            Err(e) => {
+                self.map_warnings
+                    .push(format!("Cannot read coordinates: {}", e));
+                None
+            }
+        }
     }
 
     fn construct_lat_lon_positions(&mut self) {
-        if let Some((latitudes, longitudes)) = self.extract_lat_lons() {
-            self.positions = latitudes
+        self.positions.clear();
+        self.position_rows.clear();
+        self.point_index = PointIndex::default();
+
+        if let Some(extracted) = self.extract_lat_lons() {
+            if extracted.excluded() > 0 {
+                self.map_warnings.push(format!(
+                    "{} rows excluded without valid coordinates ({} missing, {} out of range)",
+                    extracted.excluded(),
+                    extracted.missing,
+                    extracted.invalid
+                ));
+            }
+            self.positions = extracted
+                .values
                 .iter()
-                .zip(longitudes.iter())
-                .map(|(&lat, &lon)| walkers::lat_lon(lat, lon))
+                .map(|&(lat, lon)| walkers::lat_lon(lat, lon))
                 .collect();
-        } else {
-            self.positions.clear();
+            self.position_rows = extracted.rows;
+            self.point_index = PointIndex::new(&self.positions);
         }
     }
 
     fn find_h3cell_columns(&self) -> Option<String> {
-        let h3_candidates = ["h3point", "h3cell", "h3index"];
+        self.map_columns.h3cell.clone()
+    }
+
+    // Restores the columns chosen for this schema, otherwise auto-detects them
+    fn resolve_map_columns(&mut self) {
+        let Some(df) = &self.dataframe else {
+            self.map_columns = MapColumns::default();
+            return;
+        };
+        self.map_columns = load_map_columns(&schema_key(df))
+            .filter(|columns| columns.is_valid_for(df))
+            .unwrap_or_else(|| detect_map_columns(df, &self.geoparquet_columns));
+    }
 
-        let mut h3_col = None;
+    fn extract_h3cells(&mut self) -> Option<Extracted<String>> {
+        let df = self.dataframe.as_ref()?;
+        let h3_col = self.find_h3cell_columns()?;
 
-        for col in &self.column_names {
-            let col_lower = col.to_lowercase();
-            if h3_candidates.iter().any(|&name| col_lower == name) {
-                h3_col = Some(col.clone());
+        match h3_cell_rows(df, &h3_col) {
+            Ok(extracted) => Some(extracted),
+            Err(e) => {
+                self.map_warnings
+                    .push(format!("Cannot read H3 cells: {}", e));
+                None
             }
         }
-
-        h3_col
     }
 
-    fn extract_h3cells(&self) -> Option<Vec<String>> {
-        let df = self.dataframe.as_ref()?;
-        let h3_col = self.find_h3cell_columns()?;
+    fn construct_h3_cells(&mut self) {
+        self.h3cells.clear();
+        self.h3cell_rows.clear();
+
+        if let Some(extracted) = self.extract_h3cells() {
+            if extracted.excluded() > 0 {
+                self.map_warnings.push(format!(
+                    "{} rows without H3 cells excluded ({} missing, {} invalid)",
+                    extracted.excluded(),
+                    extracted.missing,
+                    extracted.invalid
+                ));
+            }
+            self.h3cells = extracted.values;
+            self.h3cell_rows = extracted.rows;
+        }
 
-        let h3_series = df.column(&h3_col).ok()?;
-        let h3_strings = h3_series.str().ok()?;
-        let h3_values: Vec<String> = h3_strings
-            .into_no_null_iter()
-            .map(|s| s.to_string())
+        // Extracted cells are valid, so every cell has a centroid
+        self.h3_centroids = self
+            .h3cells
+            .iter()
+            .map(|h3cell| {
+                let (lat, lon) = cell_center(h3cell).unwrap_or_default();
+                walkers::lat_lon(lat, lon)
+            })
             .collect();
-
-        Some(h3_values)
+        self.h3_centroid_index = PointIndex::new(&self.h3_centroids);
     }
 
-    fn construct_h3_cells(&mut self) {
-        if let Some(h3cells) = self.extract_h3cells() {
-            self.h3cells = h3cells
-        } else {
-            self.h3cells.clear();
+    fn extract_geometries(&mut self) -> Option<Extracted<Geometry>> {
+        let df = self.dataframe.as_ref()?;
+        let geometry_col = self.map_columns.geometry.clone()?;
+
+        match geometry_rows(df, &geometry_col) {
+            Ok(extracted) => Some(extracted),
+            Err(e) => {
+                self.map_warnings
+                    .push(format!("Cannot read geometries: {}", e));
+                None
+            }
         }
     }
 
-    fn render_map_data(&mut self) {
-        self.construct_lat_lon_positions();
-        self.construct_h3_cells();
+    fn construct_geometries(&mut self) {
+        self.geometries.clear();
+        self.geometry_rows.clear();
+
+        if let Some(extracted) = self.extract_geometries() {
+            if extracted.excluded() > 0 {
+                self.map_warnings.push(format!(
+                    "{} rows without geometries excluded ({} missing or empty, {} invalid)",
+                    extracted.excluded(),
+                    extracted.missing,
+                    extracted.invalid
+                ));
+            }
+            self.geometries = extracted.values;
+            self.geometry_rows = extracted.rows;
+        }
     }
 
-    fn has_mappable_columns(&self) -> bool {
-        self.find_lat_lon_columns().is_some() || self.find_h3cell_columns().is_some()
+    // Colors of the map features from their DataFrame rows
+    fn feature_colors(&self, rows: &[usize]) -> Vec<Color32> {
+        if self.map_colors.is_empty() {
+            return Vec::new();
+        }
+        rows.iter().map(|&row| self.map_colors[row]).collect()
     }
 
-    fn render_table_header(&mut self, header_row: &mut TableRow, column_names: &[String]) {
+    fn construct_map_colors(&mut self) {
+        self.map_colors.clear();
+        self.map_legend = None;
+
+        if let Some(color_by) = &self.map_color_by {
+            if !self.column_names.contains(&color_by.column_name) {
+                self.map_color_by = None;
+                return;
+            }
+            if let Some(df) = &self.dataframe {
+                match color_rows(df, color_by) {
+                    Ok((colors, legend)) => {
+                        self.map_colors = colors;
+                        self.map_legend = Some(legend);
+                    }
+                    Err(e) => self.error_message = Some(format!("Map color error: {}", e)),
+                }
+            }
+        }
+    }
+
+    fn construct_marker_styles(&mut self) {
+        self.marker_radii.clear();
+        self.marker_shapes.clear();
+
+        let style = &mut self.marker_style;
+        for column in [&mut style.size_column, &mut style.shape_column] {
+            if column
+                .as_ref()
+                .is_some_and(|c| !self.column_names.contains(c))
+            {
+                *column = None;
+            }
+        }
+        if style.shape_column.is_none() {
+            style.shape_mapping.clear();
+        }
+        let Some(df) = &self.dataframe else {
+            return;
+        };
+
+        if let Some(shape_column) = &style.shape_column
+            && style.shape_mapping.is_empty()
+        {
+            match shape_mapping(df, shape_column) {
+                Ok(mapping) => style.shape_mapping = mapping,
+                Err(e) => self.map_warnings.push(format!("Cannot map shapes: {}", e)),
+            }
+        }
+        match marker_radii(df, style) {
+            Ok(radii) => self.marker_radii = radii,
+            Err(e) => self
+                .map_warnings
+                .push(format!("Cannot size markers: {}", e)),
+        }
+        match marker_shapes(df, style) {
+            Ok(shapes) => self.marker_shapes = shapes,
+            Err(e) => self
+                .map_warnings
+                .push(format!("Cannot shape markers: {}", e)),
+        }
+    }
+
+    fn render_map_data(&mut self) {
+        self.map_warnings.clear();
+        self.construct_lat_lon_positions();
+        self.construct_h3_cells();
+        self.construct_geometries();
+        self.construct_map_colors();
+        self.construct_marker_styles();
+        self.construct_heatmap_weights();
+        self.construct_tracks();
+        self.construct_row_times();
+        self.h3_bins = None;
+        self.h3_bins_job = None;
+        self.k_ring_cells.clear();
+
+        // Filtering to the Map View must not move the map
+        if !self.viewport_filter_enabled {
+            self.map_fit_bounds = self.feature_bounds(None);
+        }
+    }
+
+    // Bins the points at the current resolution in the background, unless already binned at it
+    fn update_h3_bins(&mut self, ctx: &Context) {
+        if !self.show_h3_bins {
+            self.h3_bins = None;
+            self.h3_bins_job = None;
+            return;
+        }
+        if let Some(job) = &self.h3_bins_job
+            && let Some(result) = job.poll()
+        {
+            self.h3_bins_job = None;
+            match result.and_then(|bins| bins) {
+                Ok(bins) => self.h3_bins = Some(bins),
+                Err(e) => {
+                    self.error_message = Some(format!("H3 binning error: {}", e));
+                    self.show_h3_bins = false;
+                    return;
+                }
+            }
+        }
+        // One binning at a time, zooming on while it runs bins the latest zoom next
+        if self.h3_bins_job.is_some() {
+            return;
+        }
+
+        let resolution = self.h3_binning.resolution_at(self.map_memory.zoom());
+        if self
+            .h3_bins
+            .as_ref()
+            .is_some_and(|bins| bins.resolution == resolution)
+        {
+            return;
+        }
+        let (Some(df), Some((lat_col, lon_col))) =
+            (self.dataframe.clone(), self.find_lat_lon_columns())
+        else {
+            self.h3_bins = None;
+            return;
+        };
+        let binning = self.h3_binning.clone();
+        self.h3_bins_job = Some(BackgroundJob::spawn(ctx, move || {
+            bin_points(&df, &lat_col, &lon_col, &binning, resolution)
+        }));
+    }
+
+    fn construct_tracks(&mut self) {
+        self.tracks.clear();
+        if !self.show_tracks {
+            return;
+        }
+
+        let style = &mut self.trajectory_style;
+        for column in [&mut style.id_column, &mut style.order_column] {
+            if column
+                .as_ref()
+                .is_some_and(|c| !self.column_names.contains(c))
+            {
+                *column = None;
+            }
+        }
+        if let Some(df) = &self.dataframe {
+            match build_tracks(df, &self.position_rows, &self.trajectory_style) {
+                Ok(tracks) => self.tracks = tracks,
+                Err(e) => self
+                    .map_warnings
+                    .push(format!("Cannot build tracks: {}", e)),
+            }
+        }
+    }
+
+    fn construct_row_times(&mut self) {
+        self.row_times = None;
+        if self
+            .timeline
+            .column
+            .as_ref()
+            .is_some_and(|c| !self.column_names.contains(c))
+        {
+            self.timeline.column = None;
+        }
+
+        if let (Some(df), Some(column)) = (&self.dataframe, &self.timeline.column) {
+            match row_times(df, column) {
+                Ok(times) => {
+                    // Keep the window within the times, starting at the beginning
+                    let timeline = &mut self.timeline;
+                    if timeline.position <= times.start || timeline.position > times.end {
+                        timeline.position = (times.start + timeline.window).min(times.end);
+                    }
+                    self.row_times = Some(times);
+                }
+                Err(e) => self.map_warnings.push(format!("Cannot read times: {}", e)),
+            }
+        }
+        self.update_time_window();
+    }
+
+    // Selects the points and H3 cells within the current Time Window
+    fn update_time_window(&mut self) {
+        self.heatmap_cache.clear();
+        self.time_window = self.row_times.as_ref().map(|times| {
+            WindowFeatures::new(
+                &times.times,
+                self.timeline.window_range(),
+                (&self.positions, &self.position_rows),
+                (&self.h3cells, &self.h3_centroids, &self.h3cell_rows),
+            )
+        });
+    }
+
+    // Steps the Timeline forward while playing, stopping at the end
+    fn advance_timeline(&mut self, ctx: &Context) {
+        if !self.timeline.playing {
+            return;
+        }
+        let Some(end) = self.row_times.as_ref().map(|times| times.end) else {
+            self.timeline.playing = false;
+            return;
+        };
+        let now = Instant::now();
+        if let Some(last) = self.last_time_step
+            && now - last < PLAYBACK_INTERVAL
+        {
+            ctx.request_repaint_after(PLAYBACK_INTERVAL - (now - last));
+            return;
+        }
+
+        self.last_time_step = Some(now);
+        if self.timeline.position >= end {
+            self.timeline.playing = false;
+            return;
+        }
+        self.timeline.position = (self.timeline.position + self.timeline.step).min(end);
+        self.update_time_window();
+        ctx.request_repaint_after(PLAYBACK_INTERVAL);
+    }
+
+    fn construct_heatmap_weights(&mut self) {
+        self.heatmap_weights.clear();
+        self.heatmap_cache.clear();
+
+        let Some(column_name) = &self.heatmap_style.weight_column else {
+            return;
+        };
+        if !self.column_names.contains(column_name) {
+            self.heatmap_style.weight_column = None;
+            return;
+        }
+        if let Some(df) = &self.dataframe {
+            match numeric_values(df, column_name) {
+                Ok(values) => {
+                    self.heatmap_weights = values
+                        .into_iter()
+                        .map(|v| v.filter(|v| v.is_finite()).unwrap_or(0.0).max(0.0))
+                        .collect();
+                }
+                Err(e) => {
+                    self.map_warnings
+                        .push(format!("Heatmap weight error: {}", e));
+                    self.heatmap_style.weight_column = None;
+                }
+            }
+        }
+    }
+
+    // Bounds of the map features, optionally only those of the given rows
+    fn feature_bounds(&self, rows: Option<&BTreeSet<usize>>) -> Option<Bounds> {
+        let included = |row: &usize| rows.is_none_or(|rows| rows.contains(row));
+        let points = self
+            .positions
+            .iter()
+            .zip(&self.position_rows)
+            .filter(|(_, row)| included(row))
+            .map(|(position, _)| (position.y(), position.x()));
+        let cells = self
+            .h3cells
+            .iter()
+            .zip(&self.h3cell_rows)
+            .filter(|(_, row)| included(row))
+            .filter_map(|(h3cell, _)| cell_center(h3cell));
+        let geometries = self
+            .geometries
+            .iter()
+            .zip(&self.geometry_rows)
+            .filter(|(_, row)| included(row))
+            .flat_map(|(geometry, _)| geometry.coords());
+        Bounds::from_points(points.chain(cells).chain(geometries))
+    }
+
+    // Centers and zooms the map on the pending bounds
+    fn fit_map(&mut self, size: Vec2) {
+        let Some(bounds) = self.map_fit_bounds.take() else {
+            return;
+        };
+        self.map_memory.center_at(bounds.center());
+        // Zoom is clamped to a valid level
+        let _ = self.map_memory.set_zoom(bounds.fit_zoom(size));
+    }
+
+    fn invalidate_derived_data(&mut self) {
+        self.column_stats.clear();
+        self.column_stats_jobs.clear();
+        self.profile = None;
+        self.profile_job = None;
+        self.chart_data = None;
+        self.refresh_frequency_view();
+        self.clear_selection();
+    }
+
+    fn clear_selection(&mut self) {
+        self.selected_rows.clear();
+        self.map_selection = None;
+        self.selection_anchor = None;
+        self.inspected_row = None;
+    }
+
+    fn inspect_row(&mut self, row: usize) {
+        self.selected_rows = BTreeSet::from([row]);
+        self.selection_anchor = Some(row);
+        self.inspected_row = Some(row);
+    }
+
+    // Click selects the row, Ctrl/Cmd+Click toggles it and Shift+Click selects a range
+    fn click_table_row(&mut self, row: usize, modifiers: Modifiers) {
+        match self.selection_anchor {
+            Some(anchor) if modifiers.shift => {
+                self.selected_rows = (anchor.min(row)..=anchor.max(row)).collect();
+            }
+            _ if modifiers.command => {
+                if !self.selected_rows.remove(&row) {
+                    self.selected_rows.insert(row);
+                }
+                self.selection_anchor = Some(row);
+            }
+            _ => {
+                self.selected_rows = BTreeSet::from([row]);
+                self.selection_anchor = Some(row);
+            }
+        }
+    }
+
+    fn tooltip_columns(&self) -> Vec<String> {
+        self.map_tooltip_columns.clone().unwrap_or_else(|| {
+            self.column_names
+                .iter()
+                .take(MAX_TOOLTIP_COLUMNS)
+                .cloned()
+                .collect()
+        })
+    }
+
+    fn render_record_window(&mut self, ctx: &Context) {
+        let (Some(df), Some(row)) = (&self.dataframe, self.inspected_row) else {
+            return;
+        };
+        if row >= df.height() {
+            self.inspected_row = None;
+            return;
+        }
+
+        let values = row_values(df, row, &self.column_names);
+        let mut open = true;
+        let mut show_in_table = false;
+        egui::Window::new(format!("Row {}", row))
+            .open(&mut open)
+            .default_width(360.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("record_grid").striped(true).show(ui, |ui| {
+                        for (column, value) in &values {
+                            ui.label(RichText::new(column).strong());
+                            ui.add(Label::new(value).wrap_mode(TextWrapMode::Wrap));
+                            ui.end_row();
+                        }
+                    });
+                });
+                if ui.button("Show in Table").clicked() {
+                    show_in_table = true;
+                }
+            });
+
+        if !open {
+            self.inspected_row = None;
+        }
+        if show_in_table {
+            self.selected_tab = ViewTab::Table;
+            self.table_scroll_to_row = Some(row);
+        }
+    }
+
+    fn open_frequency_view(&mut self, column_name: &str) {
+        if let Some(df) = &self.dataframe {
+            match value_counts(df, column_name) {
+                Ok(counts) => {
+                    self.frequency_view = Some(FrequencyView::new(column_name.to_string(), counts))
+                }
+                Err(e) => self.error_message = Some(format!("Value count error: {}", e)),
+            }
+        }
+    }
+
+    fn refresh_frequency_view(&mut self) {
+        let (Some(view), Some(df)) = (&mut self.frequency_view, &self.dataframe) else {
+            return;
+        };
+        match value_counts(df, &view.column_name) {
+            Ok(counts) => view.set_counts(counts),
+            Err(_) => self.frequency_view = None,
+        }
+    }
+
+    fn render_frequency_window(&mut self, ctx: &Context) {
+        let Some(view) = &mut self.frequency_view else {
+            return;
+        };
+
+        let mut open = true;
+        let selected_values = egui::Window::new(format!("Value Counts: {}", view.column_name))
+            .open(&mut open)
+            .default_width(420.0)
+            .collapsible(false)
+            .show(ctx, |ui| view.render(ui))
+            .and_then(|r| r.inner.flatten());
+        let column_name = view.column_name.clone();
+
+        if !open {
+            self.frequency_view = None;
+        }
+
+        if let Some(values) = selected_values {
+            merge_list_selection(&mut self.filter_conditions, &column_name, values);
+            self.apply_filter();
+        }
+    }
+
+    // Column statistics tooltip, computed on a Background Thread on first hover
+    fn render_header_stats(&mut self, ui: &mut Ui, col_name: &str) {
+        if let Some(job) = self.column_stats_jobs.get(col_name)
+            && let Some(result) = job.poll()
+        {
+            self.column_stats_jobs.remove(col_name);
+            self.column_stats
+                .insert(col_name.to_string(), result.and_then(|stats| stats));
+        }
+
+        if !self.column_stats.contains_key(col_name) {
+            let profiled = self
+                .profile
+                .as_ref()
+                .and_then(|p| p.iter().find(|c| c.name == col_name))
+                .map(|c| c.stats.clone());
+            if let Some(stats) = profiled {
+                self.column_stats.insert(col_name.to_string(), stats);
+            } else if !self.column_stats_jobs.contains_key(col_name)
+                && let Some(df) = self.dataframe.clone()
+            {
+                let name = col_name.to_string();
+                let job = BackgroundJob::spawn(ui.ctx(), move || {
+                    column_stats(&df, &name).map_err(|e| e.to_string())
+                });
+                self.column_stats_jobs.insert(col_name.to_string(), job);
+            }
+        }
+
+        match self.column_stats.get(col_name) {
+            Some(Ok(stats)) => render_column_stats(ui, stats),
+            Some(Err(e)) => {
+                ui.label(format!("Statistics unavailable: {}", e));
+            }
+            None if self.column_stats_jobs.contains_key(col_name) => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Computing...");
+                });
+            }
+            None => {
+                ui.label("Statistics unavailable");
+            }
+        }
+    }
+
+    fn render_profile_tab(&mut self, ui: &mut Ui) {
+        if let Some(job) = &self.profile_job
+            && let Some(result) = job.poll()
+        {
+            self.profile_job = None;
+            match result {
+                Ok(profile) => self.profile = Some(profile),
+                Err(e) => self.error_message = Some(format!("Profile error: {}", e)),
+            }
+        }
+
+        if self.profile.is_none()
+            && self.profile_job.is_none()
+            && let Some(df) = self.dataframe.clone()
+        {
+            self.profile_job = Some(BackgroundJob::spawn(ui.ctx(), move || {
+                profile_dataframe(&df)
+            }));
+        }
+
+        if let Some(profile) = &self.profile {
+            render_profile(ui, profile);
+        } else if self.profile_job.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Profiling columns...");
+            });
+        }
+    }
+
+    fn render_chart_column_combo(
+        ui: &mut Ui,
+        id: &str,
+        label: &str,
+        selected: &mut Option<String>,
+        column_names: &[String],
+        optional: bool,
+    ) {
+        ui.label(label);
+        egui::ComboBox::from_id_salt(id)
+            .selected_text(selected.as_deref().unwrap_or("None"))
+            .show_ui(ui, |ui| {
+                if optional {
+                    ui.selectable_value(selected, None, "None");
+                }
+                for col in column_names {
+                    ui.selectable_value(selected, Some(col.clone()), col);
+                }
+            });
+    }
+
+    fn render_chart_tab(&mut self, ui: &mut Ui) {
+        if !self.column_names.contains(&self.chart_config.x_column) {
+            self.chart_config.x_column = self.column_names.first().cloned().unwrap_or_default();
+            self.chart_data = None;
+        }
+
+        let mut config = self.chart_config.clone();
+        ui.horizontal(|ui| {
+            ui.label("Chart:");
+            egui::ComboBox::from_id_salt("chart_type")
+                .selected_text(config.chart_type.to_string())
+                .show_ui(ui, |ui| {
+                    for chart_type in ChartType::ALL {
+                        ui.selectable_value(
+                            &mut config.chart_type,
+                            chart_type,
+                            chart_type.to_string(),
+                        );
+                    }
+                });
+
+            let mut x_column = Some(config.x_column.clone());
+            Self::render_chart_column_combo(
+                ui,
+                "chart_x",
+                "X:",
+                &mut x_column,
+                &self.column_names,
+                false,
+            );
+            config.x_column = x_column.unwrap_or_default();
+
+            if config.chart_type.uses_y() {
+                Self::render_chart_column_combo(
+                    ui,
+                    "chart_y",
+                    "Y:",
+                    &mut config.y_column,
+                    &self.column_names,
+                    !config.chart_type.requires_y(),
+                );
+            }
+            if config.chart_type.uses_color() {
+                Self::render_chart_column_combo(
+                    ui,
+                    "chart_color",
+                    "Color:",
+                    &mut config.color_column,
+                    &self.column_names,
+                    true,
+                );
+            }
+
+            ui.separator();
+
+            let can_export = matches!(self.chart_data, Some(Ok(_)));
+            if ui
+                .add_enabled(can_export, egui::Button::new("Export PNG"))
+                .clicked()
+                && let Some(path) = FileDialog::new().add_filter("PNG", &["png"]).save_file()
+            {
+                self.chart_png_path = Some(path);
+                ui.ctx()
+                    .send_viewport_cmd(ViewportCommand::Screenshot(egui::UserData::default()));
+            }
+            if ui
+                .add_enabled(can_export, egui::Button::new("Export SVG"))
+                .clicked()
+                && let (Some(Ok(data)), Some(path)) = (
+                    &self.chart_data,
+                    FileDialog::new().add_filter("SVG", &["svg"]).save_file(),
+                )
+            {
+                self.chart_export_result = Some(
+                    match std::fs::write(&path, chart_to_svg(data, &self.chart_config)) {
+                        Ok(_) => format!("Exported to SVG: {}", path.display()),
+                        Err(e) => format!("SVG export error: {e}"),
+                    },
+                );
+            }
+            if let Some(msg) = &self.chart_export_result {
+                ui.label(msg);
+            }
+        });
+        ui.separator();
+
+        if config != self.chart_config {
+            self.chart_config = config;
+            self.chart_data = None;
+        }
+
+        if self.chart_data.is_none()
+            && !self.chart_config.x_column.is_empty()
+            && let Some(df) = &self.dataframe
+        {
+            self.chart_data = Some(build_chart(df, &self.chart_config));
+        }
+
+        match &self.chart_data {
+            Some(Ok(data)) => {
+                self.chart_rect = Some(render_chart(ui, data, &self.chart_config).rect);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(Color32::RED, e);
+            }
+            None => {}
+        }
+    }
+
+    fn save_chart_screenshot(&mut self, ctx: &Context) {
+        let Some(path) = &self.chart_png_path else {
+            return;
+        };
+        let image = ctx.input(|i| {
+            i.raw.events.iter().find_map(|e| match e {
+                egui::Event::Screenshot { image, .. } => Some(image.clone()),
+                _ => None,
+            })
+        });
+        let (Some(image), Some(rect)) = (image, self.chart_rect) else {
+            return;
+        };
+
+        let region = image.region(&rect, Some(ctx.pixels_per_point()));
+        let [width, height] = region.size;
+        self.chart_export_result = Some(
+            match image::RgbaImage::from_raw(width as u32, height as u32, region.as_raw().to_vec())
+                .map(|img| img.save(path))
+            {
+                Some(Ok(_)) => format!("Exported to PNG: {}", path.display()),
+                Some(Err(e)) => format!("PNG export error: {e}"),
+                None => "PNG export error: invalid screenshot".to_string(),
+            },
+        );
+        self.chart_png_path = None;
+    }
+
+    fn render_map_column_selection(&mut self, ui: &mut Ui) {
+        let mut columns = self.map_columns.clone();
+        ui.horizontal(|ui| {
+            for (id, label, selected) in [
+                ("map_latitude", "Latitude:", &mut columns.latitude),
+                ("map_longitude", "Longitude:", &mut columns.longitude),
+                ("map_h3cell", "H3:", &mut columns.h3cell),
+                ("map_geometry", "Geometry:", &mut columns.geometry),
+            ] {
+                ui.label(label);
+                egui::ComboBox::from_id_salt(id)
+                    .selected_text(selected.as_deref().unwrap_or("None"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(selected, None, "None");
+                        for col in &self.column_names {
+                            ui.selectable_value(selected, Some(col.clone()), col);
+                        }
+                    });
+            }
+            if ui.button("Auto Detect").clicked()
+                && let Some(df) = &self.dataframe
+            {
+                columns = detect_map_columns(df, &self.geoparquet_columns);
+            }
+        });
+
+        if columns != self.map_columns {
+            if let Some(df) = &self.dataframe {
+                save_map_columns(&schema_key(df), &columns);
+            }
+            self.map_columns = columns;
+            self.render_map_data();
+        }
+    }
+
+    fn render_map_controls(&mut self, ui: &mut Ui) {
+        self.render_map_column_selection(ui);
+        if self.map_columns.is_empty() {
+            ui.label("Select latitude/longitude, H3 or geometry columns to plot.");
+        }
+        for warning in &self.map_warnings {
+            ui.colored_label(Color32::ORANGE, warning);
+        }
+        self.render_timeline_controls(ui);
+
+        egui::CollapsingHeader::new("Map Style").show(ui, |ui| {
+            self.render_color_by_controls(ui);
+            self.render_tooltip_column_controls(ui);
+            if !self.positions.is_empty() {
+                self.render_point_layer_controls(ui);
+            }
+
+            if !self.h3cells.is_empty() {
+                ui.horizontal(|ui| {
+                    let style = &mut self.hexagon_style;
+                    ui.label("Hexagons:");
+                    ui.color_edit_button_srgba(&mut style.fill_color);
+                    ui.add(egui::Slider::new(&mut style.fill_opacity, 0.0..=1.0).text("Opacity"));
+                    ui.color_edit_button_srgba(&mut style.stroke_color);
+                    ui.add(egui::Slider::new(&mut style.stroke_width, 0.0..=5.0).text("Stroke"));
+                    ui.checkbox(&mut self.h3_centroid_markers, "As Centroid Markers");
+                });
+                self.render_k_ring_controls(ui);
+            }
+
+            let point_markers = self.show_markers && !self.positions.is_empty();
+            let centroid_markers = self.h3_centroid_markers && !self.h3cells.is_empty();
+            if point_markers || centroid_markers {
+                self.render_marker_style_controls(ui);
+            }
+
+            if !self.geometries.is_empty() {
+                ui.horizontal(|ui| {
+                    let style = &mut self.geometry_style;
+                    ui.label("Geometries:");
+                    ui.color_edit_button_srgba(&mut style.fill_color);
+                    ui.add(egui::Slider::new(&mut style.fill_opacity, 0.0..=1.0).text("Opacity"));
+                    ui.color_edit_button_srgba(&mut style.stroke_color);
+                    ui.add(egui::Slider::new(&mut style.stroke_width, 0.0..=5.0).text("Stroke"));
+                    ui.add(
+                        egui::Slider::new(&mut style.point_radius, 1.0..=15.0).text("Point Size"),
+                    );
+                });
+            }
+        });
+        self.render_basemap_controls(ui);
+    }
+
+    fn render_basemap_controls(&mut self, ui: &mut Ui) {
+        egui::CollapsingHeader::new("Basemap").show(ui, |ui| {
+            let mut apply = false;
+            ui.horizontal(|ui| {
+                let settings = &mut self.tile_settings;
+                egui::ComboBox::from_id_salt("tile_provider")
+                    .selected_text(settings.provider.to_string())
+                    .show_ui(ui, |ui| {
+                        for provider in TileProvider::ALL {
+                            apply |= ui
+                                .selectable_value(
+                                    &mut settings.provider,
+                                    provider,
+                                    provider.to_string(),
+                                )
+                                .clicked();
+                        }
+                    });
+                // Custom and local sources are applied once configured
+                apply &= match settings.provider {
+                    TileProvider::Custom => !settings.url_template.trim().is_empty(),
+                    TileProvider::Local => settings.local_path.is_some(),
+                    _ => true,
+                };
+
+                if settings.provider == TileProvider::Local {
+                    if ui.button("Open MBTiles...").clicked()
+                        && let Some(path) = FileDialog::new()
+                            .add_filter("MBTiles", &["mbtiles"])
+                            .pick_file()
+                    {
+                        settings.local_path = Some(path);
+                        apply = true;
+                    }
+                    if ui.button("Open Folder...").clicked()
+                        && let Some(path) = FileDialog::new().pick_folder()
+                    {
+                        settings.local_path = Some(path);
+                        apply = true;
+                    }
+                    if let Some(path) = &settings.local_path {
+                        ui.label(path.display().to_string());
+                    }
+                }
+            });
+
+            if self.tile_settings.provider == TileProvider::Custom {
+                let settings = &mut self.tile_settings;
+                ui.horizontal(|ui| {
+                    ui.label("URL:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut settings.url_template)
+                            .hint_text("https://tiles.example.com/{z}/{x}/{y}.png")
+                            .desired_width(360.0),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Attribution:");
+                    ui.text_edit_singleline(&mut settings.attribution);
+                    apply |= ui.button("Apply").clicked();
+                });
+            }
+
+            if self.tile_settings.provider != TileProvider::Local {
+                let settings = &mut self.tile_settings;
+                ui.horizontal(|ui| {
+                    apply |= ui
+                        .checkbox(&mut settings.offline, "Offline (Cached Tiles Only)")
+                        .changed();
+                    ui.label("Cache:");
+                    let size = ui.add(
+                        egui::DragValue::new(&mut settings.cache_size_mb)
+                            .range(10..=100_000)
+                            .suffix(" MB"),
+                    );
+                    // Applied once edited, not on every drag step
+                    apply |= size.drag_stopped() || size.lost_focus();
+                    if ui.button("Clear Cache").clicked() {
+                        match clear_tile_cache() {
+                            Ok(_) => apply = true,
+                            Err(e) => self.tile_error = Some(format!("Tile cache error: {}", e)),
+                        }
+                    }
+                });
+                if self
+                    .tiles
+                    .remote()
+                    .is_some_and(|tiles| tiles.can_precache())
+                {
+                    self.render_precache_controls(ui);
+                }
+            }
+
+            if apply {
+                self.apply_tile_settings(ui.ctx());
+            }
+            if let Some(error) = &self.tile_error {
+                ui.colored_label(Color32::RED, error);
+            }
+        });
+    }
+
+    fn render_precache_controls(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Pre-cache View to Zoom:");
+            ui.add(egui::DragValue::new(&mut self.tile_precache_zoom).range(0..=19));
+            let viewport = self.map_viewport;
+            if ui
+                .add_enabled(viewport.is_some(), egui::Button::new("Pre-cache"))
+                .on_hover_text("Bulk downloads are only allowed from custom tile servers")
+                .clicked()
+                && let (Some(bounds), Some(tiles)) = (viewport, self.tiles.remote())
+            {
+                // Lower zoom levels are included, so zooming out also works offline
+                self.tile_error = tiles
+                    .precache(bounds, 0..=self.tile_precache_zoom)
+                    .err()
+                    .map(|e| format!("Pre-cache error: {}", e));
+            }
+            if let Some(tiles) = self.tiles.remote() {
+                let pending = tiles.pending();
+                if pending > 0 {
+                    ui.spinner();
+                    ui.label(format!("Downloading {} tiles", pending));
+                    if ui.button("Cancel").clicked() {
+                        tiles.cancel_precache();
+                    }
+                }
+            }
+        });
+    }
+
+    // Switches the Map to the chosen basemap, keeping the current one on errors
+    fn apply_tile_settings(&mut self, ctx: &Context) {
+        match create_tiles(&self.tile_settings, ctx.clone()) {
+            Ok(tiles) => {
+                self.tiles = tiles;
+                self.tile_settings.save();
+                self.tile_error = None;
+            }
+            Err(e) => self.tile_error = Some(format!("Basemap error: {}", e)),
+        }
+    }
+
+    fn render_k_ring_controls(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let mut enabled = self.k_ring_size.is_some();
+            if ui.checkbox(&mut enabled, "K-Ring on Click").changed() {
+                self.k_ring_size = enabled.then_some(1);
+                self.k_ring_cells.clear();
+            }
+            if let Some(k) = &mut self.k_ring_size {
+                ui.add(egui::Slider::new(k, 1..=10).text("k"));
+            }
+            if !self.k_ring_cells.is_empty() {
+                ui.label(format!("{} cells", self.k_ring_cells.len()));
+                if ui.button("Clear").clicked() {
+                    self.k_ring_cells.clear();
+                }
+            }
+        });
+    }
+
+    // Shows the k-ring of the clicked Hexagon
+    fn show_k_ring(&mut self, row: usize) {
+        let Some(k) = self.k_ring_size else {
+            return;
+        };
+        if let Some(index) = self.h3cell_rows.iter().position(|&r| r == row) {
+            self.k_ring_cells = k_ring(&self.h3cells[index], k);
+        }
+    }
+
+    fn render_timeline_controls(&mut self, ui: &mut Ui) {
+        let mut timeline = self.timeline.clone();
+        let detected = self.dataframe.as_ref().and_then(detect_time_column);
+
+        ui.horizontal(|ui| {
+            ui.label("Time:");
+            egui::ComboBox::from_id_salt("timeline_column")
+                .selected_text(timeline.column.as_deref().unwrap_or("None"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut timeline.column, None, "None");
+                    for col in &self.column_names {
+                        ui.selectable_value(&mut timeline.column, Some(col.clone()), col);
+                    }
+                });
+            if timeline.column.is_none()
+                && let Some(detected) = detected
+                && ui.button(format!("Animate by {}", detected)).clicked()
+            {
+                timeline.column = Some(detected);
+            }
+
+            let Some(times) = &self.row_times else {
+                return;
+            };
+            let label = if timeline.playing { "⏸" } else { "⏵" };
+            if ui.button(label).clicked() {
+                timeline.playing = !timeline.playing;
+                // Playing at the end starts over
+                if timeline.playing && timeline.position >= times.end {
+                    timeline.position = (times.start + timeline.window).min(times.end);
+                }
+            }
+            let is_time = times.is_time;
+            ui.add(
+                egui::Slider::new(&mut timeline.position, times.start..=times.end)
+                    .custom_formatter(|value, _| format_time(value, is_time)),
+            );
+            let suffix = if is_time { " s" } else { "" };
+            ui.label("Window:");
+            let window_speed = timeline.window.max(1.0) / 100.0;
+            ui.add(
+                egui::DragValue::new(&mut timeline.window)
+                    .range(0.0..=f64::MAX)
+                    .speed(window_speed)
+                    .suffix(suffix),
+            );
+            ui.label("Step:");
+            let step_speed = timeline.step.max(1.0) / 100.0;
+            ui.add(
+                egui::DragValue::new(&mut timeline.step)
+                    .range(0.0..=f64::MAX)
+                    .speed(step_speed)
+                    .suffix(suffix),
+            );
+        });
+
+        if timeline != self.timeline {
+            let column_changed = timeline.column != self.timeline.column;
+            let window_changed = timeline.window_range() != self.timeline.window_range();
+            self.timeline = timeline;
+            if column_changed {
+                self.construct_row_times();
+            } else if window_changed {
+                self.update_time_window();
+            }
+        }
+    }
+
+    fn render_point_layer_controls(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Points:");
+            ui.checkbox(&mut self.show_markers, "Markers");
+            ui.checkbox(&mut self.show_heatmap, "Heatmap");
+            ui.checkbox(&mut self.show_h3_bins, "H3 Bins");
+            if ui.checkbox(&mut self.show_tracks, "Tracks").changed() {
+                self.construct_tracks();
+            }
+        });
+        if self.show_heatmap {
+            self.render_heatmap_controls(ui);
+        }
+        if self.show_h3_bins {
+            self.render_h3_binning_controls(ui);
+        }
+        if self.show_tracks {
+            self.render_trajectory_controls(ui);
+        }
+    }
+
+    fn render_marker_style_controls(&mut self, ui: &mut Ui) {
+        let mut style = self.marker_style.clone();
+        let numeric_columns: Vec<&String> = self
+            .column_names
+            .iter()
+            .filter(|col| {
+                self.dataframe
+                    .as_ref()
+                    .and_then(|df| df.column(col).ok())
+                    .is_some_and(|c| c.dtype().is_primitive_numeric())
+            })
+            .collect();
+
+        ui.horizontal(|ui| {
+            ui.label("Markers:");
+            egui::ComboBox::from_id_salt("marker_shape")
+                .selected_text(style.shape.to_string())
+                .show_ui(ui, |ui| {
+                    for shape in MarkerShape::ALL {
+                        ui.selectable_value(&mut style.shape, shape, shape.to_string());
+                    }
+                });
+            ui.label("Size by:");
+            egui::ComboBox::from_id_salt("marker_size_column")
+                .selected_text(style.size_column.as_deref().unwrap_or("Fixed"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut style.size_column, None, "Fixed");
+                    for col in &numeric_columns {
+                        ui.selectable_value(&mut style.size_column, Some(col.to_string()), *col);
+                    }
+                });
+            if style.size_column.is_some() {
+                ui.add(egui::Slider::new(&mut style.min_radius, 1.0..=30.0).text("Min"));
+                ui.add(egui::Slider::new(&mut style.max_radius, 1.0..=30.0).text("Max"));
+            } else {
+                ui.add(egui::Slider::new(&mut style.radius, 1.0..=30.0).text("Size"));
+            }
+            ui.add(egui::Slider::new(&mut style.opacity, 0.0..=1.0).text("Opacity"));
+            ui.color_edit_button_srgba(&mut style.stroke_color);
+            ui.add(egui::Slider::new(&mut style.stroke_width, 0.0..=5.0).text("Stroke"));
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Shape by:");
+            let shape_column = style.shape_column.clone();
+            egui::ComboBox::from_id_salt("marker_shape_column")
+                .selected_text(style.shape_column.as_deref().unwrap_or("None"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut style.shape_column, None, "None");
+                    for col in &self.column_names {
+                        ui.selectable_value(&mut style.shape_column, Some(col.clone()), col);
+                    }
+                });
+            // A new column gets its own category mapping
+            if style.shape_column != shape_column {
+                style.shape_mapping.clear();
+            }
+            for (i, (category, shape)) in style.shape_mapping.iter_mut().enumerate() {
+                ui.label(category.as_str());
+                egui::ComboBox::from_id_salt(format!("marker_category_shape_{}", i))
+                    .selected_text(shape.to_string())
+                    .show_ui(ui, |ui| {
+                        for option in MarkerShape::ALL {
+                            ui.selectable_value(shape, option, option.to_string());
+                        }
+                    });
+            }
+        });
+
+        if style != self.marker_style {
+            let restyle_rows = style.row_styles_differ(&self.marker_style);
+            self.marker_style = style;
+            if restyle_rows {
+                self.construct_marker_styles();
+            }
+        }
+    }
+
+    fn render_trajectory_controls(&mut self, ui: &mut Ui) {
+        let mut style = self.trajectory_style.clone();
+        // Gaps in time columns are in seconds
+        let time_order = style.order_column.as_ref().is_some_and(|col| {
+            self.dataframe
+                .as_ref()
+                .and_then(|df| df.column(col).ok())
+                .is_some_and(|c| seconds_per_unit(c.dtype()).is_some())
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Tracks:");
+            for (id, label, selected, none) in [
+                ("track_id", "ID:", &mut style.id_column, "None"),
+                (
+                    "track_order",
+                    "Order:",
+                    &mut style.order_column,
+                    "Row Order",
+                ),
+            ] {
+                ui.label(label);
+                egui::ComboBox::from_id_salt(id)
+                    .selected_text(selected.as_deref().unwrap_or(none))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(selected, None, none);
+                        for col in &self.column_names {
+                            ui.selectable_value(selected, Some(col.clone()), col);
+                        }
+                    });
+            }
+
+            let mut split = style.gap_threshold.is_some();
+            if ui.checkbox(&mut split, "Split Gaps").changed() {
+                style.gap_threshold = split.then_some(if time_order { 300.0 } else { 1.0 });
+            }
+            if let Some(threshold) = &mut style.gap_threshold {
+                let suffix = if time_order { " s" } else { "" };
+                ui.add(
+                    egui::DragValue::new(threshold)
+                        .range(0.0..=f64::MAX)
+                        .suffix(suffix),
+                );
+            }
+            ui.checkbox(&mut style.show_arrows, "Arrows");
+            ui.add(egui::Slider::new(&mut style.line_width, 1.0..=8.0).text("Width"));
+        });
+
+        if style != self.trajectory_style {
+            // Width and Arrows only change how the tracks are drawn
+            let rebuild = style.id_column != self.trajectory_style.id_column
+                || style.order_column != self.trajectory_style.order_column
+                || style.gap_threshold != self.trajectory_style.gap_threshold;
+            self.trajectory_style = style;
+            if rebuild {
+                self.construct_tracks();
+            }
+        }
+    }
+
+    fn render_heatmap_controls(&mut self, ui: &mut Ui) {
+        let mut style = self.heatmap_style.clone();
+
+        ui.horizontal(|ui| {
+            ui.label("Heatmap:");
+            ui.add(egui::Slider::new(&mut style.radius, 5.0..=60.0).text("Radius"));
+            ui.add(egui::Slider::new(&mut style.intensity, 0.1..=5.0).text("Intensity"));
+            egui::ComboBox::from_id_salt("heatmap_colormap")
+                .selected_text(style.colormap.to_string())
+                .show_ui(ui, |ui| {
+                    for colormap in Colormap::ALL {
+                        ui.selectable_value(&mut style.colormap, colormap, colormap.to_string());
+                    }
+                });
+            ui.label("Weight:");
+            egui::ComboBox::from_id_salt("heatmap_weight")
+                .selected_text(style.weight_column.as_deref().unwrap_or("None"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut style.weight_column, None, "None");
+                    for col in &self.column_names {
+                        ui.selectable_value(&mut style.weight_column, Some(col.clone()), col);
+                    }
+                });
+        });
+
+        if style != self.heatmap_style {
+            let weight_changed = style.weight_column != self.heatmap_style.weight_column;
+            self.heatmap_style = style;
+            if weight_changed {
+                self.construct_heatmap_weights();
+            }
+        }
+    }
+
+    fn render_h3_binning_controls(&mut self, ui: &mut Ui) {
+        let mut binning = self.h3_binning.clone();
+
+        ui.horizontal(|ui| {
+            ui.label("H3 Bins:");
+            ui.checkbox(&mut binning.auto_resolution, "Auto Resolution");
+            match &self.h3_bins {
+                Some(bins) if binning.auto_resolution => {
+                    ui.label(format!("({})", bins.resolution));
+                }
+                _ => {
+                    ui.add(egui::Slider::new(&mut binning.resolution, 0..=15).text("Resolution"));
+                }
+            }
+
+            egui::ComboBox::from_id_salt("h3_bin_function")
+                .selected_text(binning.function.to_string())
+                .show_ui(ui, |ui| {
+                    for function in AggFunction::ALL {
+                        ui.selectable_value(&mut binning.function, function, function.to_string());
+                    }
+                });
+            egui::ComboBox::from_id_salt("h3_bin_column")
+                .selected_text(binning.value_column.as_deref().unwrap_or("Points"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut binning.value_column, None, "Points");
+                    for col in &self.column_names {
+                        ui.selectable_value(&mut binning.value_column, Some(col.clone()), col);
+                    }
+                });
+            egui::ComboBox::from_id_salt("h3_bin_colormap")
+                .selected_text(binning.colormap.to_string())
+                .show_ui(ui, |ui| {
+                    for colormap in Colormap::ALL {
+                        ui.selectable_value(&mut binning.colormap, colormap, colormap.to_string());
+                    }
+                });
+            if self.h3_bins_job.is_some() {
+                ui.spinner();
+            }
+        });
+
+        if binning != self.h3_binning {
+            self.h3_binning = binning;
+            self.h3_bins = None;
+            self.h3_bins_job = None;
+        }
+    }
+
+    fn render_tooltip_column_controls(&mut self, ui: &mut Ui) {
+        let mut tooltip_columns = self.tooltip_columns();
+        ui.horizontal(|ui| {
+            ui.label("Tooltip:");
+            ui.menu_button(format!("{} columns", tooltip_columns.len()), |ui| {
+                for col in &self.column_names {
+                    let mut shown = tooltip_columns.contains(col);
+                    if ui.checkbox(&mut shown, col).changed() {
+                        if shown {
+                            tooltip_columns.push(col.clone());
+                        } else {
+                            tooltip_columns.retain(|c| c != col);
+                        }
+                    }
+                }
+            });
+        });
+
+        if tooltip_columns != self.tooltip_columns() {
+            // Keep the table column order
+            tooltip_columns = self
+                .column_names
+                .iter()
+                .filter(|c| tooltip_columns.contains(c))
+                .cloned()
+                .collect();
+            self.map_tooltip_columns = Some(tooltip_columns);
+        }
+    }
+
+    fn render_color_by_controls(&mut self, ui: &mut Ui) {
+        let mut color_by = self.map_color_by.clone();
+
+        ui.horizontal(|ui| {
+            ui.label("Color by:");
+            let mut column = color_by.as_ref().map(|c| c.column_name.clone());
+            egui::ComboBox::from_id_salt("map_color_column")
+                .selected_text(column.as_deref().unwrap_or("None"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut column, None, "None");
+                    for col in &self.column_names {
+                        ui.selectable_value(&mut column, Some(col.clone()), col);
+                    }
+                });
+            if column != color_by.as_ref().map(|c| c.column_name.clone()) {
+                color_by = column.map(ColorBy::new);
+            }
+
+            if let (Some(color_by), Some(Legend::Continuous { min, max, .. })) =
+                (&mut color_by, &self.map_legend)
+            {
+                egui::ComboBox::from_id_salt("map_colormap")
+                    .selected_text(color_by.colormap.to_string())
+                    .show_ui(ui, |ui| {
+                        for colormap in Colormap::ALL {
+                            ui.selectable_value(
+                                &mut color_by.colormap,
+                                colormap,
+                                colormap.to_string(),
+                            );
+                        }
+                    });
+
+                let mut clamp_min = color_by.min.unwrap_or(*min);
+                let mut clamp_max = color_by.max.unwrap_or(*max);
+                ui.label("Min:");
+                if ui.add(egui::DragValue::new(&mut clamp_min)).changed() {
+                    color_by.min = Some(clamp_min);
+                }
+                ui.label("Max:");
+                if ui.add(egui::DragValue::new(&mut clamp_max)).changed() {
+                    color_by.max = Some(clamp_max);
+                }
+                if ui.button("Auto").clicked() {
+                    color_by.min = None;
+                    color_by.max = None;
+                }
+            }
+        });
+
+        if color_by != self.map_color_by {
+            self.map_color_by = color_by;
+            self.construct_map_colors();
+        }
+    }
+
+    fn render_map(&mut self, ui: &mut Ui) {
+        self.render_map_controls(ui);
+        self.render_map_selection_controls(ui);
+
+        let position = self
+            .positions
+            .last()
+            .cloned()
+            .unwrap_or_else(|| walkers::lon_lat(0.0, 52.0));
+
+        let mut hovered_point = HoveredFeature::default();
+        let mut hovered_hexagon = HoveredFeature::default();
+        let mut hovered_bin = HoveredFeature::default();
+        let mut hovered_geometry = HoveredFeature::default();
+        // Tracks are not rows, so they are hovered by index
+        let mut hovered_track = HoveredFeature::default();
+        let mut completed_selection = None;
+        let mut viewport_bounds = None;
+        let mut pointer_position = None;
+        // Room is left for the status bar below the Map
+        let status_bar_height = ui.spacing().interact_size.y + ui.spacing().item_spacing.y;
+        let map_size = ui.available_size() - Vec2::new(0.0, status_bar_height);
+        self.fit_map(map_size);
+        self.update_h3_bins(ui.ctx());
+        self.advance_timeline(ui.ctx());
+        // Bins are not rows, so they are hovered by index and never selected
+        let bin_indices: Vec<usize> =
+            (0..self.h3_bins.as_ref().map_or(0, |b| b.cells.len())).collect();
+        let no_selection = BTreeSet::new();
+
+        // Only the points and H3 cells within the Time Window are drawn
+        let (position_rows, point_index, h3cells, h3cell_rows, h3_centroid_index) =
+            match &self.time_window {
+                Some(window) => (
+                    &window.position_rows,
+                    &window.point_index,
+                    &window.h3cells,
+                    &window.h3cell_rows,
+                    &window.h3_centroid_index,
+                ),
+                None => (
+                    &self.position_rows,
+                    &self.point_index,
+                    &self.h3cells,
+                    &self.h3cell_rows,
+                    &self.h3_centroid_index,
+                ),
+            };
+
+        // Colors are looked up before the Map mutably borrows the tiles
+        let hexagon_colors = (!self.h3_centroid_markers).then(|| self.feature_colors(h3cell_rows));
+
+        let mut map = walkers::Map::new(Some(&mut self.tiles), &mut self.map_memory, position)
+            .panning(self.selection_tool == SelectionTool::Pan);
+        if let Some(hexagon_colors) = hexagon_colors {
+            map = map.with_plugin(
+                HexagonPlot::new(h3cells.clone(), hexagon_colors, self.hexagon_style)
+                    .with_interaction(h3cell_rows, &self.selected_rows, &mut hovered_hexagon),
+            );
+        } else {
+            map = map.with_plugin(
+                PointPlot::new(
+                    h3_centroid_index,
+                    h3cell_rows,
+                    &self.map_colors,
+                    &self.marker_style,
+                )
+                .with_row_styles(&self.marker_radii, &self.marker_shapes)
+                .with_interaction(&self.selected_rows, &mut hovered_hexagon),
+            );
+        }
+        if !self.k_ring_cells.is_empty() {
+            let colors = vec![K_RING_STYLE.fill_color; self.k_ring_cells.len()];
+            map = map.with_plugin(HexagonPlot::new(
+                self.k_ring_cells.clone(),
+                colors,
+                K_RING_STYLE,
+            ));
+        }
+        if let Some(bins) = &self.h3_bins {
+            map = map.with_plugin(
+                HexagonPlot::new(bins.cells.clone(), bins.colors.clone(), self.hexagon_style)
+                    .with_interaction(&bin_indices, &no_selection, &mut hovered_bin),
+            );
+        }
+        if !self.tracks.is_empty() {
+            map = map.with_plugin(
+                TrajectoryPlot::new(&self.tracks, &self.positions, &self.trajectory_style)
+                    .with_interaction(&mut hovered_track),
+            );
+        }
+        if !self.geometries.is_empty() {
+            map = map.with_plugin(
+                GeometryPlot::new(
+                    &self.geometries,
+                    &self.geometry_rows,
+                    &self.map_colors,
+                    self.geometry_style,
+                )
+                .with_interaction(&self.selected_rows, &mut hovered_geometry),
+            );
+        }
+        if self.show_heatmap {
+            map = map.with_plugin(HeatmapPlot::new(
+                point_index,
+                position_rows,
+                &self.heatmap_weights,
+                &self.heatmap_style,
+                &mut self.heatmap_cache,
+            ));
+        }
+        if self.show_markers {
+            map = map.with_plugin(
+                PointPlot::new(
+                    point_index,
+                    position_rows,
+                    &self.map_colors,
+                    &self.marker_style,
+                )
+                .with_row_styles(&self.marker_radii, &self.marker_shapes)
+                .with_interaction(&self.selected_rows, &mut hovered_point),
+            );
+        }
+        map = map
+            .with_plugin(SelectionPlot::new(
+                self.selection_tool,
+                &mut self.selection_path,
+                &mut completed_selection,
+            ))
+            .with_plugin(MeasurePlot::new(
+                self.measure_tool,
+                &mut self.measure_points,
+            ))
+            .with_plugin(ViewportBounds::new(&mut viewport_bounds))
+            .with_plugin(PointerPosition::new(&mut pointer_position));
+        let legend = match &self.h3_bins {
+            Some(bins) => Some(&bins.legend),
+            None => self.map_legend.as_ref(),
+        };
+        if let Some(legend) = legend {
+            map = map.with_plugin(LegendPlot::new(legend.clone()));
+        }
+        let response = ui.add_sized(map_size, map);
+
+        self.map_cursor = pointer_position;
+        if response.secondary_clicked() {
+            self.map_context_position = pointer_position;
+        }
+        response.context_menu(|ui| self.render_map_context_menu(ui));
+        self.render_map_status_bar(ui);
+
+        if let Some(polygon) = completed_selection {
+            self.select_map_area(polygon);
+        }
+        self.map_viewport = viewport_bounds;
+        if let Some((south_west, north_east)) = viewport_bounds {
+            self.update_viewport_filter(ui.ctx(), south_west, north_east);
+        }
+
+        // Points and geometries are drawn above bins and hexagons, so they take precedence
+        let hovered_shape = hovered_point.row.or(hovered_geometry.row);
+        if let (None, Some(track)) = (hovered_shape, hovered_track.row) {
+            let track = &self.tracks[track];
+            response.on_hover_ui_at_pointer(|ui| {
+                egui::Grid::new("map_track_tooltip_grid").show(ui, |ui| {
+                    ui.label("Track");
+                    ui.label(&track.id);
+                    ui.end_row();
+                    ui.label("Points");
+                    ui.label(track.points.len().to_string());
+                    ui.end_row();
+                });
+            });
+            return;
+        }
+        if let (None, Some(bin), Some(bins)) = (hovered_shape, hovered_bin.row, &self.h3_bins) {
+            response.on_hover_ui_at_pointer(|ui| {
+                egui::Grid::new("map_bin_tooltip_grid").show(ui, |ui| {
+                    ui.label("H3 Cell");
+                    ui.label(&bins.cells[bin]);
+                    ui.end_row();
+                    ui.label("Points");
+                    ui.label(bins.counts[bin].to_string());
+                    ui.end_row();
+                    if bins.value_name != "count" {
+                        ui.label(&bins.value_name);
+                        ui.label(bins.values[bin].map_or("null".to_string(), |v| v.to_string()));
+                        ui.end_row();
+                    }
+                });
+            });
+            return;
+        }
+        let Some(row) = hovered_shape.or(hovered_hexagon.row) else {
+            return;
+        };
+        if let Some(df) = &self.dataframe {
+            let values = row_values(df, row, &self.tooltip_columns());
+            response.clone().on_hover_ui_at_pointer(|ui| {
+                ui.label(RichText::new(format!("Row {}", row)).strong());
+                egui::Grid::new("map_tooltip_grid").show(ui, |ui| {
+                    for (column, value) in &values {
+                        ui.label(column);
+                        ui.label(value);
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+        // Clicks add measure points instead while measuring
+        if response.clicked() && self.measure_tool == MeasureTool::Off {
+            self.inspect_row(row);
+            if hovered_shape.is_none() {
+                self.show_k_ring(row);
+            }
+        }
+    }
+
+    fn render_map_status_bar(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let position = self.map_cursor.map_or("-".to_string(), format_position);
+            ui.label(format!("Lat, Lon: {}", position))
+                .on_hover_text("Right-click the map to copy the coordinates");
+
+            let mut show_cell = self.cursor_h3_resolution.is_some();
+            if ui.checkbox(&mut show_cell, "H3 Cell").changed() {
+                self.cursor_h3_resolution = show_cell.then_some(9);
+            }
+            if let Some(resolution) = &mut self.cursor_h3_resolution {
+                ui.add(
+                    egui::DragValue::new(resolution)
+                        .range(0..=15)
+                        .prefix("Res "),
+                );
+                if let Some(cell) = self.map_cursor.and_then(|p| position_cell(p, *resolution)) {
+                    ui.label(cell);
+                }
+            }
+
+            if self.measure_tool == MeasureTool::Off {
+                return;
+            }
+            ui.separator();
+            match measurement_summary(self.measure_tool, &self.measure_points) {
+                Some(summary) => {
+                    ui.label(&summary);
+                    if ui
+                        .button("Copy")
+                        .on_hover_text("Copy with the WKT")
+                        .clicked()
+                    {
+                        let wkt = measurement_wkt(self.measure_tool, &self.measure_points);
+                        ui.ctx().copy_text(format!("{}\n{}", summary, wkt));
+                    }
+                }
+                None => {
+                    ui.label("Click on the map to add points");
+                }
+            }
+            if ui
+                .add_enabled(!self.measure_points.is_empty(), egui::Button::new("Undo"))
+                .clicked()
+            {
+                self.measure_points.pop();
+            }
+            if ui
+                .add_enabled(!self.measure_points.is_empty(), egui::Button::new("Clear"))
+                .clicked()
+            {
+                self.measure_points.clear();
+            }
+        });
+    }
+
+    fn render_map_context_menu(&mut self, ui: &mut Ui) {
+        let Some(position) = self.map_context_position else {
+            ui.close();
+            return;
+        };
+        let coordinates = format_position(position);
+        if ui.button(format!("Copy {}", coordinates)).clicked() {
+            ui.ctx().copy_text(coordinates);
+            ui.close();
+        }
+        let resolution = self.cursor_h3_resolution.unwrap_or(9);
+        if let Some(cell) = position_cell(position, resolution)
+            && ui.button(format!("Copy H3 Cell {}", cell)).clicked()
+        {
+            ui.ctx().copy_text(cell);
+            ui.close();
+        }
+    }
+
+    fn render_map_selection_controls(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Zoom to Data").clicked() {
+                self.map_fit_bounds = self.feature_bounds(None);
+            }
+            if ui
+                .add_enabled(
+                    !self.selected_rows.is_empty(),
+                    egui::Button::new("Zoom to Selection"),
+                )
+                .clicked()
+            {
+                self.map_fit_bounds = self.feature_bounds(Some(&self.selected_rows));
+            }
+            ui.separator();
+
+            ui.label("Drag:");
+            for tool in SelectionTool::ALL {
+                ui.selectable_value(&mut self.selection_tool, tool, tool.to_string());
+            }
+            ui.separator();
+
+            ui.label("Measure:");
+            for tool in MeasureTool::ALL {
+                if ui
+                    .selectable_value(&mut self.measure_tool, tool, tool.to_string())
+                    .changed()
+                {
+                    self.measure_points.clear();
+                }
+            }
+            ui.separator();
+
+            ui.label(format!("{} rows selected", self.selected_rows.len()));
+            if ui
+                .add_enabled(!self.selected_rows.is_empty(), egui::Button::new("Clear"))
+                .clicked()
+            {
+                self.clear_selection();
+            }
+            if ui
+                .add_enabled(
+                    self.map_selection.is_some(),
+                    egui::Button::new("Filter to Selection"),
+                )
+                .clicked()
+                && let Some(polygon) = self.map_selection.take()
+            {
+                self.spatial_filter = Some(SpatialFilter::new(&polygon, self.map_columns.clone()));
+                self.apply_filter();
+            }
+            if self.spatial_filter.is_some() && ui.button("Clear Spatial Filter").clicked() {
+                self.spatial_filter = None;
+                self.apply_filter();
+            }
+            ui.separator();
+
+            if ui
+                .checkbox(&mut self.viewport_filter_enabled, "Filter Table to View")
+                .changed()
+                && !self.viewport_filter_enabled
+            {
+                self.pending_viewport_filter = None;
+                if self.viewport_filter.take().is_some() {
+                    self.apply_filter();
+                }
+            }
+        });
+    }
+
+    // Filters to the visible map area once panning/zooming has paused
+    fn update_viewport_filter(
+        &mut self,
+        ctx: &Context,
+        south_west: Position,
+        north_east: Position,
+    ) {
+        if !self.viewport_filter_enabled || self.map_columns.is_empty() {
+            return;
+        }
+        let filter = SpatialFilter::from_bounds(south_west, north_east, self.map_columns.clone());
+        if self.viewport_filter.as_ref() == Some(&filter) {
+            self.pending_viewport_filter = None;
+            return;
+        }
+
+        // Stop following the data, so filtering doesn't move the map
+        if self.map_memory.detached().is_none() {
+            let center = walkers::lon_lat(
+                (south_west.x() + north_east.x()) / 2.0,
+                (south_west.y() + north_east.y()) / 2.0,
+            );
+            self.map_memory.center_at(center);
+        }
+
+        match &self.pending_viewport_filter {
+            Some((pending, changed_at)) if *pending == filter => {
+                let elapsed = changed_at.elapsed();
+                if elapsed >= VIEWPORT_FILTER_DELAY {
+                    self.pending_viewport_filter = None;
+                    self.viewport_filter = Some(filter);
+                    self.apply_filter();
+                } else {
+                    ctx.request_repaint_after(VIEWPORT_FILTER_DELAY - elapsed);
+                }
+            }
+            _ => {
+                self.pending_viewport_filter = Some((filter, Instant::now()));
+                ctx.request_repaint_after(VIEWPORT_FILTER_DELAY);
+            }
+        }
+    }
+
+    // Selects the rows inside the area drawn on the map
+    fn select_map_area(&mut self, polygon: Vec<Position>) {
+        let Some(df) = &self.dataframe else {
+            return;
+        };
+        let area = SpatialFilter::new(&polygon, self.map_columns.clone());
+        match rows_in_polygon(df, &area.columns, &area.polygon) {
+            Ok(rows) => {
+                self.selected_rows = rows;
+                self.selection_anchor = None;
+                self.map_selection = Some(polygon);
+            }
+            Err(e) => self.error_message = Some(format!("Map selection error: {}", e)),
+        }
+    }
+
+    fn render_table_header(&mut self, header_row: &mut TableRow, column_names: &[String]) {
         for col_name in column_names {
             header_row.col(|ui| {
                 // Determine sort indicator
@@ -482,7 +3149,7 @@ impl Parqr {
                     ""
                 };
 
-                if ui
+                let response = ui
                     .add(
                         Label::new(
                             RichText::new(format!("{} {}", col_name, sort_indicator)).strong(),
@@ -490,8 +3157,16 @@ impl Parqr {
                         .wrap_mode(TextWrapMode::Extend),
                     )
                     .on_hover_cursor(CursorIcon::Default)
-                    .clicked()
-                {
+                    .on_hover_ui(|ui| self.render_header_stats(ui, col_name));
+
+                response.context_menu(|ui| {
+                    if ui.button("Value Counts").clicked() {
+                        self.open_frequency_view(col_name);
+                        ui.close();
+                    }
+                });
+
+                if response.clicked() {
                     // Toggle sort or set new sort condition
                     if let Some(sort_cond) = &mut self.sort_condition {
                         if sort_cond.column_name == *col_name {
@@ -516,20 +3191,36 @@ impl Parqr {
 
     fn render_table(&mut self, ui: &mut Ui) {
         if let Some(df) = &self.dataframe.clone() {
+            let mut clicked_row = None;
             ScrollArea::horizontal()
                 .auto_shrink([false, false])
                 .show(ui, |ui| {
-                    TableBuilder::new(ui)
+                    let mut table = TableBuilder::new(ui);
+                    if let Some(row) = self.table_scroll_to_row.take() {
+                        table = table.scroll_to_row(row, Some(egui::Align::Center));
+                    }
+                    table
                         .striped(true)
+                        .sense(Sense::click())
                         .resizable(true)
                         .columns(Column::auto().resizable(true), self.column_names.len() + 1)
                         .header(25.0, |mut header_row| {
                             self.render_table_header(&mut header_row, &self.column_names.clone());
                         })
                         .body(|body| {
-                            render_table_body(body, df, &self.column_names);
+                            clicked_row = render_table_body(
+                                body,
+                                df,
+                                &self.column_names,
+                                &self.selected_rows,
+                            );
                         });
                 });
+
+            if let Some(row) = clicked_row {
+                let modifiers = ui.input(|i| i.modifiers);
+                self.click_table_row(row, modifiers);
+            }
         }
     }
 
@@ -708,6 +3399,13 @@ impl Parqr {
     }
 }
 
+// Reorders per row values to follow sorted rows (empty values are left as is)
+fn permute_rows<T: Clone>(values: &mut Vec<T>, order: &[usize]) {
+    if values.len() == order.len() {
+        *values = order.iter().map(|&row| values[row].clone()).collect();
+    }
+}
+
 impl eframe::App for Parqr {
     fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
         let font_size = 18.;
@@ -717,10 +3415,16 @@ impl eframe::App for Parqr {
         });
 
         self.process_pending_files();
+        self.save_chart_screenshot(ctx);
 
         CentralPanel::default().show(ctx, |ui| {
             self.render_file_selector(ui);
             self.render_filter_dialog(ui);
+            self.render_computed_dialog(ui);
+            self.render_group_by_dialog(ui);
+            self.render_h3_tools_dialog(ui);
+            self.render_frequency_window(ctx);
+            self.render_record_window(ctx);
 
             ui.separator();
             self.render_error_message(ui);
@@ -732,13 +3436,23 @@ impl eframe::App for Parqr {
                 {
                     self.selected_tab = ViewTab::Table;
                 }
-                if self.has_mappable_columns() {
-                    if ui
-                        .selectable_label(matches!(self.selected_tab, ViewTab::Map), "Map")
-                        .clicked()
-                    {
-                        self.selected_tab = ViewTab::Map;
-                    }
+                if ui
+                    .selectable_label(matches!(self.selected_tab, ViewTab::Profile), "Profile")
+                    .clicked()
+                {
+                    self.selected_tab = ViewTab::Profile;
+                }
+                if ui
+                    .selectable_label(matches!(self.selected_tab, ViewTab::Chart), "Chart")
+                    .clicked()
+                {
+                    self.selected_tab = ViewTab::Chart;
+                }
+                if ui
+                    .selectable_label(matches!(self.selected_tab, ViewTab::Map), "Map")
+                    .clicked()
+                {
+                    self.selected_tab = ViewTab::Map;
                 }
                 if ui
                     .selectable_label(matches!(self.selected_tab, ViewTab::Export), "Export")
@@ -753,18 +3467,14 @@ impl eframe::App for Parqr {
                 ViewTab::Table => {
                     self.render_table(ui);
                 }
+                ViewTab::Profile => {
+                    self.render_profile_tab(ui);
+                }
+                ViewTab::Chart => {
+                    self.render_chart_tab(ui);
+                }
                 ViewTab::Map => {
-                    let position = self
-                        .positions
-                        .last()
-                        .cloned()
-                        .unwrap_or_else(|| walkers::lon_lat(0.0, 52.0));
-
-                    let map =
-                        walkers::Map::new(Some(&mut self.tiles), &mut self.map_memory, position)
-                            .with_plugin(PointPlot::new(self.positions.clone()))
-                            .with_plugin(HexagonPlot::new(self.h3cells.clone()));
-                    ui.add(map);
+                    self.render_map(ui);
                 }
                 ViewTab::Export => {
                     self.render_export_pane(ui);
@@ -0,0 +1,255 @@
+use ::eframe::egui::Color32;
+use h3o::{CellIndex, LatLng, Resolution};
+use polars::prelude::*;
+
+use crate::df::aggregate::AggFunction;
+use crate::map::colormap::{ColorBy, Colormap, Legend, color_rows};
+use crate::map::extract::lat_lon_rows;
+
+// Screen length of a hexagon edge when the resolution follows the zoom
+const AUTO_EDGE_PIXELS: f64 = 16.0;
+// Mean H3 edge length at resolution 0, shrinking by the aperture ratio per resolution
+const RESOLUTION_0_EDGE_METERS: f64 = 1_107_712.0;
+const RESOLUTION_RATIO: f64 = 2.6458;
+// Web Mercator meters per pixel at zoom 0 (equator)
+const ZOOM_0_METERS_PER_PIXEL: f64 = 156_543.0;
+
+// Binning of lat/lon Points into H3 Cells
+#[derive(Debug, Clone, PartialEq)]
+pub struct H3Binning {
+    pub resolution: u8,
+    // Follow the map zoom instead of the fixed resolution
+    pub auto_resolution: bool,
+    pub function: AggFunction,
+    // Aggregated column (bins count their points without one)
+    pub value_column: Option<String>,
+    pub colormap: Colormap,
+}
+
+impl Default for H3Binning {
+    fn default() -> Self {
+        Self {
+            resolution: 7,
+            auto_resolution: true,
+            function: AggFunction::Count,
+            value_column: None,
+            colormap: Colormap::Viridis,
+        }
+    }
+}
+
+impl H3Binning {
+    /// Resolution used at the map zoom.
+    pub fn resolution_at(&self, zoom: f64) -> u8 {
+        if !self.auto_resolution {
+            return self.resolution;
+        }
+        let meters_per_pixel = ZOOM_0_METERS_PER_PIXEL / 2f64.powf(zoom);
+        let edge = AUTO_EDGE_PIXELS * meters_per_pixel;
+        let resolution = (RESOLUTION_0_EDGE_METERS / edge).ln() / RESOLUTION_RATIO.ln();
+        resolution.round().clamp(0.0, 15.0) as u8
+    }
+
+    fn value_name(&self) -> String {
+        match &self.value_column {
+            Some(column) => format!("{}({})", self.function, column),
+            None => "count".to_string(),
+        }
+    }
+}
+
+// H3 Cells aggregating the Points inside them
+pub struct H3Bins {
+    pub resolution: u8,
+    pub cells: Vec<String>,
+    pub counts: Vec<u32>,
+    pub values: Vec<Option<f64>>,
+    pub value_name: String,
+    pub colors: Vec<Color32>,
+    pub legend: Legend,
+}
+
+fn point_cell(lat: f64, lon: f64, resolution: Resolution) -> Option<u64> {
+    LatLng::new(lat, lon)
+        .ok()
+        .map(|latlng| u64::from(latlng.to_cell(resolution)))
+}
+
+/// Bins the lat/lon points into H3 cells at the resolution, colored by their aggregate.
+pub fn bin_points(
+    df: &DataFrame,
+    lat_column: &str,
+    lon_column: &str,
+    binning: &H3Binning,
+    resolution: u8,
+) -> Result<H3Bins, String> {
+    let h3_resolution = Resolution::try_from(resolution).map_err(|e| e.to_string())?;
+    let extracted = lat_lon_rows(df, lat_column, lon_column).map_err(|e| e.to_string())?;
+
+    let cells: Vec<Option<u64>> = extracted
+        .values
+        .iter()
+        .map(|&(lat, lon)| point_cell(lat, lon, h3_resolution))
+        .collect();
+    let mut points = Column::new("h3cell".into(), cells).into_frame();
+    if let Some(value_column) = &binning.value_column {
+        let rows = IdxCa::from_vec(
+            "rows".into(),
+            extracted.rows.iter().map(|&row| row as IdxSize).collect(),
+        );
+        let values = df
+            .column(value_column)
+            .and_then(|c| c.take(&rows))
+            .map_err(|e| e.to_string())?;
+        points
+            .with_column(values.with_name("value".into()))
+            .map_err(|e| e.to_string())?;
+    }
+
+    let value_name = binning.value_name();
+    let mut aggregations = vec![len().cast(DataType::UInt32).alias("count")];
+    // Without a value column, bins are valued by their count
+    if binning.value_column.is_some() {
+        aggregations.push(
+            binning
+                .function
+                .apply(col("value"))
+                .cast(DataType::Float64)
+                .alias(value_name.as_str()),
+        );
+    }
+    let bins = points
+        .lazy()
+        .filter(col("h3cell").is_not_null())
+        .group_by([col("h3cell")])
+        .agg(aggregations)
+        .collect()
+        .map_err(|e| e.to_string())?;
+
+    let (colors, legend) = color_rows(
+        &bins,
+        &ColorBy {
+            colormap: binning.colormap,
+            ..ColorBy::new(value_name.clone())
+        },
+    )?;
+
+    let column = |name: &str| bins.column(name).map_err(|e| e.to_string());
+    let cells = column("h3cell")?
+        .u64()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|cell| {
+            cell.and_then(|cell| CellIndex::try_from(cell).ok())
+                .map(|cell| cell.to_string())
+                .unwrap_or_default()
+        })
+        .collect();
+    let counts = column("count")?
+        .u32()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|count| count.unwrap_or(0))
+        .collect();
+    let values = column(&value_name)?
+        .cast(&DataType::Float64)
+        .map_err(|e| e.to_string())?
+        .f64()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .collect();
+
+    Ok(H3Bins {
+        resolution,
+        cells,
+        counts,
+        values,
+        value_name,
+        colors,
+        legend,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points() -> DataFrame {
+        df!(
+            "lat" => [Some(37.7750), Some(37.7751), Some(40.7128), None, Some(95.0)],
+            "lon" => [Some(-122.4194), Some(-122.4195), Some(-74.0060), Some(0.0), Some(0.0)],
+            "fare" => [10.0, 20.0, 5.0, 100.0, 100.0],
+        )
+        .unwrap()
+    }
+
+    // (cell, count, value) sorted by cell
+    fn sorted_bins(bins: &H3Bins) -> Vec<(String, u32, Option<f64>)> {
+        let mut rows: Vec<_> = bins
+            .cells
+            .iter()
+            .cloned()
+            .zip(bins.counts.iter().copied())
+            .zip(bins.values.iter().copied())
+            .map(|((cell, count), value)| (cell, count, value))
+            .collect();
+        rows.sort_by(|a, b| a.0.cmp(&b.0));
+        rows
+    }
+
+    #[test]
+    fn counts_points_per_cell() {
+        let binning = H3Binning::default();
+        let bins = bin_points(&points(), "lat", "lon", &binning, 7).unwrap();
+        assert_eq!(bins.value_name, "count");
+        assert_eq!(
+            sorted_bins(&bins),
+            vec![
+                ("872830828ffffff".to_string(), 2, Some(2.0)),
+                ("872a1072cffffff".to_string(), 1, Some(1.0)),
+            ]
+        );
+        assert_eq!(bins.colors.len(), 2);
+    }
+
+    #[test]
+    fn aggregates_value_column_per_cell() {
+        let binning = H3Binning {
+            function: AggFunction::Sum,
+            value_column: Some("fare".to_string()),
+            ..Default::default()
+        };
+        let bins = bin_points(&points(), "lat", "lon", &binning, 7).unwrap();
+        assert_eq!(bins.value_name, "sum(fare)");
+        let values: Vec<(u32, Option<f64>)> = sorted_bins(&bins)
+            .into_iter()
+            .map(|(_, count, value)| (count, value))
+            .collect();
+        assert_eq!(values, vec![(2, Some(30.0)), (1, Some(5.0))]);
+    }
+
+    #[test]
+    fn invalid_resolution_is_an_error() {
+        assert!(bin_points(&points(), "lat", "lon", &H3Binning::default(), 16).is_err());
+    }
+
+    #[test]
+    fn resolution_follows_zoom() {
+        let auto = H3Binning::default();
+        assert_eq!(auto.resolution_at(0.0), 0);
+        assert_eq!(auto.resolution_at(10.0), 6);
+        assert_eq!(auto.resolution_at(15.0), 10);
+        assert_eq!(auto.resolution_at(25.0), 15);
+        let resolutions: Vec<u8> = (0..=22)
+            .map(|zoom| auto.resolution_at(zoom as f64))
+            .collect();
+        assert!(resolutions.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let fixed = H3Binning {
+            resolution: 4,
+            auto_resolution: false,
+            ..Default::default()
+        };
+        assert_eq!(fixed.resolution_at(18.0), 4);
+    }
+}
@@ -0,0 +1,125 @@
+use ::eframe::egui::Vec2;
+use std::f64::consts::PI;
+use walkers::Position;
+
+// Tile size of the Web Mercator zoom levels
+const TILE_SIZE: f64 = 256.0;
+const MAX_FIT_ZOOM: f64 = 16.0;
+// Fraction of the Map the fitted data fills
+const FIT_PADDING: f64 = 0.9;
+// Web Mercator latitude limit
+const MAX_LATITUDE: f64 = 85.05;
+
+// Bounding Box of Map Features
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+// Web Mercator y of a latitude, from 0 (north) to 1 (south)
+fn mercator_y(lat: f64) -> f64 {
+    let lat = lat.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+    (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0
+}
+
+fn mercator_lat(y: f64) -> f64 {
+    (PI * (1.0 - 2.0 * y)).sinh().atan().to_degrees()
+}
+
+impl Bounds {
+    /// Bounds of (lat, lon) points, None when there are none.
+    pub fn from_points(points: impl IntoIterator<Item = (f64, f64)>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, (lat, lon)| {
+            Some(match bounds {
+                None => Bounds {
+                    south: lat,
+                    west: lon,
+                    north: lat,
+                    east: lon,
+                },
+                Some(b) => Bounds {
+                    south: b.south.min(lat),
+                    west: b.west.min(lon),
+                    north: b.north.max(lat),
+                    east: b.east.max(lon),
+                },
+            })
+        })
+    }
+
+    pub fn center(&self) -> Position {
+        let y = (mercator_y(self.north) + mercator_y(self.south)) / 2.0;
+        walkers::lon_lat((self.west + self.east) / 2.0, mercator_lat(y))
+    }
+
+    /// Highest zoom level at which the bounds fit in a map of the given size.
+    pub fn fit_zoom(&self, size: Vec2) -> f64 {
+        let width = (self.east - self.west) / 360.0;
+        let height = mercator_y(self.south) - mercator_y(self.north);
+
+        let zoom_for = |pixels: f32, extent: f64| {
+            if extent <= 0.0 {
+                MAX_FIT_ZOOM
+            } else {
+                (pixels as f64 * FIT_PADDING / (extent * TILE_SIZE)).log2()
+            }
+        };
+        zoom_for(size.x, width)
+            .min(zoom_for(size.y, height))
+            .clamp(0.0, MAX_FIT_ZOOM)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: Vec2 = Vec2::new(800.0, 600.0);
+
+    #[test]
+    fn single_point_is_centered_at_max_zoom() {
+        let bounds = Bounds::from_points([(48.85, 2.35)]).unwrap();
+        assert_eq!(
+            bounds,
+            Bounds {
+                south: 48.85,
+                west: 2.35,
+                north: 48.85,
+                east: 2.35
+            }
+        );
+        let center = bounds.center();
+        assert!((center.y() - 48.85).abs() < 1e-9 && (center.x() - 2.35).abs() < 1e-9);
+        assert_eq!(bounds.fit_zoom(SIZE), MAX_FIT_ZOOM);
+    }
+
+    #[test]
+    fn empty_layer_has_no_bounds() {
+        assert_eq!(Bounds::from_points(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn points_near_antimeridian_stay_in_range() {
+        let east = Bounds::from_points([(10.0, 179.5), (-10.0, 179.9)]).unwrap();
+        assert!((east.center().x() - 179.7).abs() < 1e-9);
+        assert!(east.center().y().abs() < 1e-9);
+        assert!(east.fit_zoom(SIZE) > 4.0);
+
+        // Bounds don't wrap, so both sides span the whole world
+        let across = Bounds::from_points([(0.0, 179.0), (0.0, -179.0)]).unwrap();
+        assert_eq!((across.west, across.east), (-179.0, 179.0));
+        assert_eq!(across.center().x(), 0.0);
+        assert!(across.fit_zoom(SIZE) < 2.0);
+    }
+
+    #[test]
+    fn mercator_latitude_round_trips() {
+        for lat in [-80.0, -45.0, 0.0, 30.0, 85.0] {
+            assert!((mercator_lat(mercator_y(lat)) - lat).abs() < 1e-9);
+        }
+        assert_eq!(mercator_y(0.0), 0.5);
+    }
+}
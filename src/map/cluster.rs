@@ -0,0 +1,338 @@
+use ::eframe::egui::{Pos2, Rect, Vec2, pos2, vec2};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use walkers::{Position, Projector};
+
+// Cells per side of the Web Mercator grid indexing the points
+const GRID_SIZE: u32 = 1 << 14;
+// Screen size of the cells points are clustered into
+const CLUSTER_CELL: f32 = 48.0;
+// Most visible points drawn individually before clustering
+const MAX_MARKERS: usize = 20_000;
+
+// Web Mercator (x, y), from 0 to 1 across the world with y growing south
+fn mercator(position: &Position) -> (f64, f64) {
+    let lat = position.y().clamp(-85.05, 85.05).to_radians();
+    let x = (position.x() + 180.0) / 360.0;
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0;
+    (x, y)
+}
+
+fn grid_coordinate(value: f64) -> u32 {
+    ((value * GRID_SIZE as f64) as u32).min(GRID_SIZE - 1)
+}
+
+// Non-empty Grid Cell, holding a run of the ordered points
+struct GridCell {
+    id: u32,
+    start: usize,
+    count: usize,
+    // Mean Web Mercator coordinates of its points
+    center: (f64, f64),
+}
+
+// Spatial Index of Map Points on a uniform Web Mercator grid
+#[derive(Default)]
+pub struct PointIndex {
+    // Web Mercator coordinates ordered by grid cell
+    mercator: Vec<(f64, f64)>,
+    // Index (into the indexed positions) of each ordered point
+    points: Vec<u32>,
+    cells: Vec<GridCell>,
+}
+
+impl PointIndex {
+    pub fn new(positions: &[Position]) -> Self {
+        let mut ordered: Vec<(u32, (f64, f64), u32)> = positions
+            .iter()
+            .enumerate()
+            .map(|(i, position)| {
+                let (x, y) = mercator(position);
+                let id = grid_coordinate(y) * GRID_SIZE + grid_coordinate(x);
+                (id, (x, y), i as u32)
+            })
+            .collect();
+        ordered.sort_unstable_by_key(|(id, _, _)| *id);
+
+        let mut cells: Vec<GridCell> = Vec::new();
+        for (i, (id, (x, y), _)) in ordered.iter().enumerate() {
+            match cells.last_mut() {
+                Some(cell) if cell.id == *id => {
+                    cell.count += 1;
+                    cell.center.0 += x;
+                    cell.center.1 += y;
+                }
+                _ => cells.push(GridCell {
+                    id: *id,
+                    start: i,
+                    count: 1,
+                    center: (*x, *y),
+                }),
+            }
+        }
+        for cell in cells.iter_mut() {
+            cell.center = (
+                cell.center.0 / cell.count as f64,
+                cell.center.1 / cell.count as f64,
+            );
+        }
+
+        Self {
+            mercator: ordered.iter().map(|(_, m, _)| *m).collect(),
+            points: ordered.iter().map(|(_, _, i)| *i).collect(),
+            cells,
+        }
+    }
+
+    // Cells overlapping the Web Mercator rectangle
+    fn cells_within(&self, min: (f64, f64), max: (f64, f64)) -> impl Iterator<Item = &GridCell> {
+        let (x0, x1) = (grid_coordinate(min.0), grid_coordinate(max.0));
+        let (y0, y1) = (grid_coordinate(min.1), grid_coordinate(max.1));
+        (y0..=y1).flat_map(move |y| {
+            let (first, last) = (y * GRID_SIZE + x0, y * GRID_SIZE + x1);
+            let start = self.cells.partition_point(|cell| cell.id < first);
+            self.cells[start..]
+                .iter()
+                .take_while(move |cell| cell.id <= last)
+        })
+    }
+}
+
+// Maps Web Mercator coordinates to the screen for the current view
+struct ScreenTransform {
+    origin: Pos2,
+    origin_mercator: (f64, f64),
+    // Pixels per Web Mercator unit
+    scale: f64,
+}
+
+impl ScreenTransform {
+    // Derived from positions across the view, keeping precision at high zooms
+    fn new(projector: &Projector, rect: Rect) -> Self {
+        let half_width = vec2(rect.width() / 2.0, 0.0);
+        let center = projector.unproject(rect.center().to_vec2());
+        let (west, east) = (
+            projector.unproject(rect.center().to_vec2() - half_width),
+            projector.unproject(rect.center().to_vec2() + half_width),
+        );
+
+        let origin = projector.project(center).to_pos2();
+        let pixels = (projector.project(east).x - projector.project(west).x) as f64;
+        let extent = mercator(&east).0 - mercator(&west).0;
+        let scale = if extent > 0.0 { pixels / extent } else { 1.0 };
+        let origin_mercator = mercator(&center);
+        Self {
+            origin,
+            origin_mercator,
+            scale,
+        }
+    }
+
+    fn to_screen(&self, (x, y): (f64, f64)) -> Pos2 {
+        pos2(
+            self.origin.x + ((x - self.origin_mercator.0) * self.scale) as f32,
+            self.origin.y + ((y - self.origin_mercator.1) * self.scale) as f32,
+        )
+    }
+
+    fn to_mercator(&self, pos: Pos2) -> (f64, f64) {
+        (
+            self.origin_mercator.0 + (pos.x - self.origin.x) as f64 / self.scale,
+            self.origin_mercator.1 + (pos.y - self.origin.y) as f64 / self.scale,
+        )
+    }
+}
+
+// Group of nearby points drawn as a single bubble
+pub struct Cluster {
+    pub center: Pos2,
+    pub count: usize,
+    // The point, when the cluster holds only one
+    pub point: Option<usize>,
+}
+
+// What is drawn for the visible area
+pub enum VisiblePoints {
+    // (point index, screen position) of individual markers
+    Markers(Vec<(usize, Pos2)>),
+    Clusters(Vec<Cluster>),
+}
+
+#[derive(Default)]
+struct ClusterSum {
+    count: usize,
+    x: f64,
+    y: f64,
+    point: Option<usize>,
+}
+
+impl ClusterSum {
+    fn add(&mut self, (x, y): (f64, f64), count: usize, point: Option<usize>) {
+        self.point = if self.count == 0 { point } else { None };
+        self.count += count;
+        self.x += x * count as f64;
+        self.y += y * count as f64;
+    }
+}
+
+impl PointIndex {
+    // Screen transform and the cells within the view expanded by the margin
+    fn view(
+        &self,
+        projector: &Projector,
+        rect: Rect,
+        margin: f32,
+    ) -> (ScreenTransform, Vec<&GridCell>) {
+        let transform = ScreenTransform::new(projector, rect);
+        let min = transform.to_mercator(rect.min - Vec2::splat(margin));
+        let max = transform.to_mercator(rect.max + Vec2::splat(margin));
+        let cells = self.cells_within(min, max).collect();
+        (transform, cells)
+    }
+
+    /// Visits the screen position and (point, weight) of each point within the margin of the view.
+    ///
+    /// Grid cells smaller than `detail` pixels are visited once at their center, with no point
+    /// and their point count as weight.
+    pub fn visit(
+        &self,
+        projector: &Projector,
+        rect: Rect,
+        margin: f32,
+        detail: f32,
+        mut visit: impl FnMut(Pos2, Option<usize>, f64),
+    ) {
+        let (transform, cells) = self.view(projector, rect, margin);
+        let expanded = rect.expand(margin);
+        let merge_cells = transform.scale / (GRID_SIZE as f64) <= detail as f64;
+
+        for cell in cells {
+            if merge_cells {
+                let pos = transform.to_screen(cell.center);
+                if expanded.contains(pos) {
+                    visit(pos, None, cell.count as f64);
+                }
+                continue;
+            }
+            for i in cell.start..cell.start + cell.count {
+                let pos = transform.to_screen(self.mercator[i]);
+                if expanded.contains(pos) {
+                    visit(pos, Some(self.points[i] as usize), 1.0);
+                }
+            }
+        }
+    }
+
+    /// Points inside the view, individually or clustered on a screen grid when there are many.
+    pub fn visible(&self, projector: &Projector, rect: Rect) -> VisiblePoints {
+        // Markers partly inside the view are still drawn
+        let margin = CLUSTER_CELL;
+        let (transform, cells) = self.view(projector, rect, margin);
+        let expanded = rect.expand(margin);
+        let total: usize = cells.iter().map(|cell| cell.count).sum();
+
+        let points_of = |cell: &&GridCell| cell.start..cell.start + cell.count;
+        if total <= MAX_MARKERS {
+            let markers = cells
+                .iter()
+                .flat_map(points_of)
+                .map(|i| {
+                    (
+                        self.points[i] as usize,
+                        transform.to_screen(self.mercator[i]),
+                    )
+                })
+                .filter(|(_, pos)| expanded.contains(*pos))
+                .collect();
+            return VisiblePoints::Markers(markers);
+        }
+
+        // Clusters are anchored to the world, so they don't shift while panning
+        let cluster_size = CLUSTER_CELL as f64 / transform.scale;
+        let mut sums: HashMap<(i64, i64), ClusterSum> = HashMap::new();
+        let mut add = |mercator: (f64, f64), count: usize, point: Option<usize>| {
+            if !expanded.contains(transform.to_screen(mercator)) {
+                return;
+            }
+            let key = (
+                (mercator.0 / cluster_size).floor() as i64,
+                (mercator.1 / cluster_size).floor() as i64,
+            );
+            sums.entry(key).or_default().add(mercator, count, point);
+        };
+
+        // Grid cells much smaller than a cluster are added whole
+        let cell_pixels = transform.scale / GRID_SIZE as f64;
+        if cell_pixels <= (CLUSTER_CELL / 4.0) as f64 {
+            for cell in &cells {
+                let point = (cell.count == 1).then(|| self.points[cell.start] as usize);
+                add(cell.center, cell.count, point);
+            }
+        } else {
+            for i in cells.iter().flat_map(points_of) {
+                add(self.mercator[i], 1, Some(self.points[i] as usize));
+            }
+        }
+
+        let clusters = sums
+            .into_values()
+            .map(|sum| Cluster {
+                center: transform.to_screen((sum.x / sum.count as f64, sum.y / sum.count as f64)),
+                count: sum.count,
+                point: sum.point,
+            })
+            .collect();
+        VisiblePoints::Clusters(clusters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walkers::{MapMemory, lon_lat};
+
+    #[test]
+    fn screen_transform_matches_projector() {
+        // View away from the screen origin, as in a panel next to the table
+        let rect = Rect::from_min_size(pos2(300.0, 120.0), vec2(800.0, 600.0));
+        let mut memory = MapMemory::default();
+        memory.center_at(lon_lat(13.4, 52.5));
+        memory.set_zoom(12.0).unwrap();
+        let projector = Projector::new(rect, &memory, lon_lat(0.0, 0.0));
+        let transform = ScreenTransform::new(&projector, rect);
+
+        for position in [
+            lon_lat(13.4, 52.5),
+            lon_lat(13.35, 52.48),
+            lon_lat(13.47, 52.53),
+        ] {
+            let expected = projector.project(position).to_pos2();
+            let actual = transform.to_screen(mercator(&position));
+            assert!(
+                actual.distance(expected) < 0.5,
+                "{actual:?} != {expected:?}"
+            );
+            let back = transform.to_mercator(expected);
+            let (x, y) = mercator(&position);
+            assert!((back.0 - x).abs() < 1e-9 && (back.1 - y).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn visible_markers_are_inside_view() {
+        let rect = Rect::from_min_size(pos2(300.0, 120.0), vec2(800.0, 600.0));
+        let mut memory = MapMemory::default();
+        memory.center_at(lon_lat(0.0, 0.0));
+        memory.set_zoom(4.0).unwrap();
+        let projector = Projector::new(rect, &memory, lon_lat(0.0, 0.0));
+        let index = PointIndex::new(&[lon_lat(0.0, 0.0), lon_lat(1.0, 1.0), lon_lat(120.0, 0.0)]);
+
+        let VisiblePoints::Markers(mut markers) = index.visible(&projector, rect) else {
+            panic!("expected markers");
+        };
+        markers.sort_by_key(|(point, _)| *point);
+        assert_eq!(markers.len(), 2);
+        assert!(markers[0].1.distance(rect.center()) < 0.5);
+        assert_eq!(markers[1].0, 1);
+    }
+}
@@ -0,0 +1,295 @@
+use ::eframe::egui::{Align2, Color32, FontId, Pos2, Rect, Response, Stroke, Ui, Vec2, pos2, vec2};
+use polars::prelude::*;
+use walkers::{MapMemory, Plugin, Projector};
+
+use crate::chart::data::numeric_values;
+use crate::df::frequency::value_counts;
+use crate::ui::palette::{CATEGORICAL, categorical};
+
+const OTHER_COLOR: Color32 = Color32::GRAY;
+const NULL_COLOR: Color32 = Color32::from_rgb(60, 60, 60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Colormap {
+    Viridis,
+    Magma,
+    Diverging,
+}
+
+impl Colormap {
+    pub const ALL: [Colormap; 3] = [Colormap::Viridis, Colormap::Magma, Colormap::Diverging];
+
+    fn stops(&self) -> &'static [[u8; 3]] {
+        match self {
+            Colormap::Viridis => &[
+                [68, 1, 84],
+                [59, 82, 139],
+                [33, 145, 140],
+                [94, 201, 98],
+                [253, 231, 37],
+            ],
+            Colormap::Magma => &[
+                [0, 0, 4],
+                [81, 18, 124],
+                [183, 55, 121],
+                [252, 137, 97],
+                [252, 253, 191],
+            ],
+            // Red-Blue (reversed so low values are blue)
+            Colormap::Diverging => &[
+                [33, 102, 172],
+                [146, 197, 222],
+                [247, 247, 247],
+                [244, 165, 130],
+                [178, 24, 43],
+            ],
+        }
+    }
+
+    /// Interpolates the colormap at `t` in [0, 1].
+    pub fn color(&self, t: f64) -> Color32 {
+        let stops = self.stops();
+        let t = if t.is_finite() {
+            t.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let position = t * (stops.len() - 1) as f64;
+        let i = (position.floor() as usize).min(stops.len() - 2);
+        let fraction = position - i as f64;
+        let [r0, g0, b0] = stops[i];
+        let [r1, g1, b1] = stops[i + 1];
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * fraction).round() as u8;
+        Color32::from_rgb(lerp(r0, r1), lerp(g0, g1), lerp(b0, b1))
+    }
+}
+
+impl std::fmt::Display for Colormap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Colormap::Viridis => write!(f, "Viridis"),
+            Colormap::Magma => write!(f, "Magma"),
+            Colormap::Diverging => write!(f, "Diverging"),
+        }
+    }
+}
+
+// Data Column driving the Map Feature Colors
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorBy {
+    pub column_name: String,
+    pub colormap: Colormap,
+    // Clamping Range for Numeric Columns (defaults to the data range)
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl ColorBy {
+    pub fn new(column_name: String) -> Self {
+        Self {
+            column_name,
+            colormap: Colormap::Viridis,
+            min: None,
+            max: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Legend {
+    Continuous {
+        title: String,
+        colormap: Colormap,
+        min: f64,
+        max: f64,
+    },
+    Categorical {
+        title: String,
+        entries: Vec<(String, Color32)>,
+    },
+}
+
+/// Computes a color for every row of the DataFrame, with the legend describing the mapping.
+pub fn color_rows(df: &DataFrame, color_by: &ColorBy) -> Result<(Vec<Color32>, Legend), String> {
+    let dtype = df
+        .column(&color_by.column_name)
+        .map_err(|e| e.to_string())?
+        .dtype()
+        .clone();
+
+    if dtype.is_primitive_numeric() || dtype.is_temporal() {
+        continuous_colors(df, color_by)
+    } else {
+        categorical_colors(df, &color_by.column_name)
+    }
+}
+
+fn continuous_colors(df: &DataFrame, color_by: &ColorBy) -> Result<(Vec<Color32>, Legend), String> {
+    let values = numeric_values(df, &color_by.column_name)?;
+    let finite = values.iter().flatten().filter(|v| v.is_finite());
+    let data_min = finite.clone().copied().fold(f64::INFINITY, f64::min);
+    let data_max = finite.copied().fold(f64::NEG_INFINITY, f64::max);
+
+    let min = color_by.min.unwrap_or(data_min);
+    let max = color_by.max.unwrap_or(data_max);
+    let range = if max > min { max - min } else { 1.0 };
+
+    let colors = values
+        .iter()
+        .map(|v| match v {
+            Some(v) if v.is_finite() => color_by.colormap.color((v - min) / range),
+            _ => NULL_COLOR,
+        })
+        .collect();
+
+    Ok((
+        colors,
+        Legend::Continuous {
+            title: color_by.column_name.clone(),
+            colormap: color_by.colormap,
+            min,
+            max,
+        },
+    ))
+}
+
+// Most frequent categories get a palette color, the remainder are grouped as "Other"
+fn categorical_colors(df: &DataFrame, column_name: &str) -> Result<(Vec<Color32>, Legend), String> {
+    let counts = value_counts(df, column_name).map_err(|e| e.to_string())?;
+    let categories: Vec<String> = counts
+        .iter()
+        .filter_map(|vc| vc.value.clone())
+        .take(CATEGORICAL.len())
+        .collect();
+
+    let values = df
+        .column(column_name)
+        .and_then(|c| c.cast(&DataType::String))
+        .map_err(|e| e.to_string())?;
+    let values = values.str().map_err(|e| e.to_string())?;
+
+    let colors = values
+        .iter()
+        .map(|v| match v {
+            Some(v) => categories
+                .iter()
+                .position(|c| c == v)
+                .map_or(OTHER_COLOR, categorical),
+            None => NULL_COLOR,
+        })
+        .collect();
+
+    let mut entries: Vec<(String, Color32)> = categories
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (c.clone(), categorical(i)))
+        .collect();
+    if counts.len() > categories.len() {
+        entries.push(("Other".to_string(), OTHER_COLOR));
+    }
+
+    Ok((
+        colors,
+        Legend::Categorical {
+            title: column_name.to_string(),
+            entries,
+        },
+    ))
+}
+
+// Map Legend Plugin (drawn in the bottom left corner)
+pub struct LegendPlot {
+    legend: Legend,
+}
+
+impl LegendPlot {
+    pub fn new(legend: Legend) -> Self {
+        Self { legend }
+    }
+}
+
+const LEGEND_MARGIN: f32 = 10.0;
+const LEGEND_WIDTH: f32 = 180.0;
+const LEGEND_ROW_HEIGHT: f32 = 16.0;
+
+impl Plugin for LegendPlot {
+    fn run(
+        self: Box<Self>,
+        ui: &mut Ui,
+        response: &Response,
+        _projector: &Projector,
+        _map_memory: &MapMemory,
+    ) {
+        let painter = ui.painter();
+        let font = FontId::proportional(12.0);
+        let text_color = Color32::BLACK;
+
+        let (title, rows) = match &self.legend {
+            Legend::Continuous { title, .. } => (title, 2),
+            Legend::Categorical { title, entries } => (title, entries.len()),
+        };
+        let height = LEGEND_ROW_HEIGHT * (rows as f32 + 1.0) + LEGEND_MARGIN;
+        let rect = Rect::from_min_size(
+            pos2(
+                response.rect.left() + LEGEND_MARGIN,
+                response.rect.bottom() - LEGEND_MARGIN - height,
+            ),
+            vec2(LEGEND_WIDTH, height),
+        );
+        painter.rect_filled(rect, 4.0, Color32::WHITE.gamma_multiply(0.85));
+
+        let origin = rect.min + Vec2::splat(LEGEND_MARGIN / 2.0);
+        painter.text(origin, Align2::LEFT_TOP, title, font.clone(), text_color);
+        let body = origin + vec2(0.0, LEGEND_ROW_HEIGHT);
+
+        match &self.legend {
+            Legend::Continuous {
+                colormap, min, max, ..
+            } => {
+                let bar_width = LEGEND_WIDTH - LEGEND_MARGIN;
+                let steps = 32;
+                for i in 0..steps {
+                    let x = body.x + bar_width * i as f32 / steps as f32;
+                    let step = Rect::from_min_max(
+                        Pos2::new(x, body.y),
+                        Pos2::new(x + bar_width / steps as f32 + 0.5, body.y + 10.0),
+                    );
+                    painter.rect_filled(step, 0.0, colormap.color(i as f64 / (steps - 1) as f64));
+                }
+                let labels_y = body.y + LEGEND_ROW_HEIGHT;
+                painter.text(
+                    Pos2::new(body.x, labels_y),
+                    Align2::LEFT_TOP,
+                    format!("{:.4}", min),
+                    font.clone(),
+                    text_color,
+                );
+                painter.text(
+                    Pos2::new(body.x + bar_width, labels_y),
+                    Align2::RIGHT_TOP,
+                    format!("{:.4}", max),
+                    font,
+                    text_color,
+                );
+            }
+            Legend::Categorical { entries, .. } => {
+                for (i, (label, color)) in entries.iter().enumerate() {
+                    let y = body.y + LEGEND_ROW_HEIGHT * i as f32;
+                    painter.circle(
+                        Pos2::new(body.x + 5.0, y + 6.0),
+                        5.0,
+                        *color,
+                        Stroke::new(1.0, Color32::BLACK),
+                    );
+                    painter.text(
+                        Pos2::new(body.x + 16.0, y),
+                        Align2::LEFT_TOP,
+                        label,
+                        font.clone(),
+                        text_color,
+                    );
+                }
+            }
+        }
+    }
+}
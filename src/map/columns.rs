@@ -0,0 +1,445 @@
+use h3o::CellIndex;
+use polars::prelude::*;
+use std::str::FromStr;
+
+use crate::config::store::{KeyValueStore, stable_hash};
+use crate::map::geometry::{parse_geometry_text, parse_wkb};
+
+const SAMPLE_SIZE: usize = 1_000;
+// Geometries are slower to parse, so fewer are sampled
+const GEOMETRY_SAMPLE_SIZE: usize = 100;
+const STORE_FILE: &str = "map_columns.tsv";
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+// Columns plotted on the Map
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MapColumns {
+    pub latitude: Option<String>,
+    pub longitude: Option<String>,
+    pub h3cell: Option<String>,
+    // WKT/WKB geometry column
+    pub geometry: Option<String>,
+}
+
+impl MapColumns {
+    pub fn lat_lon(&self) -> Option<(String, String)> {
+        Some((self.latitude.clone()?, self.longitude.clone()?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lat_lon().is_none() && self.h3cell.is_none() && self.geometry.is_none()
+    }
+
+    /// Whether every selected column exists in the DataFrame.
+    pub fn is_valid_for(&self, df: &DataFrame) -> bool {
+        [
+            &self.latitude,
+            &self.longitude,
+            &self.h3cell,
+            &self.geometry,
+        ]
+        .into_iter()
+        .flatten()
+        .all(|name| df.column(name).is_ok())
+    }
+
+    fn encode(&self) -> String {
+        [
+            &self.latitude,
+            &self.longitude,
+            &self.h3cell,
+            &self.geometry,
+        ]
+        .map(|c| c.clone().unwrap_or_default())
+        .join(&FIELD_SEPARATOR.to_string())
+    }
+
+    fn decode(value: &str) -> Self {
+        let mut fields = value
+            .split(FIELD_SEPARATOR)
+            .map(|f| (!f.is_empty()).then(|| f.to_string()));
+        Self {
+            latitude: fields.next().flatten(),
+            longitude: fields.next().flatten(),
+            h3cell: fields.next().flatten(),
+            geometry: fields.next().flatten(),
+        }
+    }
+}
+
+/// Key identifying the DataFrame schema (column names and types).
+pub fn schema_key(df: &DataFrame) -> String {
+    let schema: Vec<String> = df
+        .columns()
+        .iter()
+        .map(|c| format!("{}:{}", c.name(), c.dtype()))
+        .collect();
+    stable_hash(&schema.join(","))
+}
+
+/// Returns the columns previously chosen for the schema.
+pub fn load_map_columns(schema_key: &str) -> Option<MapColumns> {
+    KeyValueStore::open(STORE_FILE)
+        .get(schema_key)
+        .map(MapColumns::decode)
+}
+
+/// Remembers the columns chosen for the schema.
+pub fn save_map_columns(schema_key: &str, columns: &MapColumns) {
+    KeyValueStore::open(STORE_FILE).set(schema_key, &columns.encode());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Axis {
+    Latitude,
+    Longitude,
+}
+
+impl Axis {
+    fn range(&self) -> f64 {
+        match self {
+            Axis::Latitude => 90.0,
+            Axis::Longitude => 180.0,
+        }
+    }
+
+    // Name tokens, strongest first
+    fn tokens(&self) -> &'static [&'static str] {
+        match self {
+            Axis::Latitude => &["latitude", "lat", "y"],
+            Axis::Longitude => &["longitude", "lon", "lng", "long", "x"],
+        }
+    }
+}
+
+// Splits the name into lowercase tokens (snake_case, kebab-case, spaces and camelCase)
+fn name_tokens(name: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower && !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_lowercase() || c.is_numeric();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+// Name score, with the remaining tokens (the prefix used to pair lat/lon columns)
+fn name_score(name: &str, axis: Axis) -> Option<(u32, String)> {
+    let tokens = name_tokens(name);
+    let lower = name.to_lowercase();
+    for (rank, token) in axis.tokens().iter().enumerate() {
+        let strength = (axis.tokens().len() - rank) as u32;
+        if lower == *token {
+            return Some((strength * 10 + 5, String::new()));
+        }
+        if let Some(i) = tokens.iter().position(|t| t == token) {
+            let mut prefix = tokens.clone();
+            prefix.remove(i);
+            return Some((strength * 10, prefix.join("_")));
+        }
+    }
+    None
+}
+
+// Sample of non-null values cast to f64 (None if the column is not coordinate-like)
+fn sample_values(df: &DataFrame, name: &str) -> Option<Vec<f64>> {
+    let column = df.column(name).ok()?;
+    let dtype = column.dtype();
+    if !(dtype.is_primitive_numeric() || dtype.is_string() || dtype.is_decimal()) {
+        return None;
+    }
+
+    let sample = column
+        .as_materialized_series()
+        .drop_nulls()
+        .head(Some(SAMPLE_SIZE))
+        .cast(&DataType::Float64)
+        .ok()?;
+    let values: Vec<f64> = sample.f64().ok()?.into_iter().flatten().collect();
+    (!values.is_empty()).then_some(values)
+}
+
+fn in_range(values: &[f64], axis: Axis) -> bool {
+    values
+        .iter()
+        .all(|v| v.is_finite() && v.abs() <= axis.range())
+}
+
+fn candidates(df: &DataFrame, axis: Axis) -> Vec<(String, u32, String)> {
+    df.get_column_names()
+        .iter()
+        .filter_map(|name| {
+            let (score, prefix) = name_score(name, axis)?;
+            let values = sample_values(df, name)?;
+            in_range(&values, axis).then(|| (name.to_string(), score, prefix))
+        })
+        .collect()
+}
+
+fn detect_lat_lon(df: &DataFrame) -> Option<(String, String)> {
+    let latitudes = candidates(df, Axis::Latitude);
+    let longitudes = candidates(df, Axis::Longitude);
+
+    // Prefer pairs sharing a prefix (e.g. pickup_lat / pickup_lon), then the strongest names
+    latitudes
+        .iter()
+        .flat_map(|lat| longitudes.iter().map(move |lon| (lat, lon)))
+        .filter(|(lat, lon)| lat.0 != lon.0)
+        .max_by_key(|(lat, lon)| (lat.2 == lon.2, lat.1 + lon.1))
+        .map(|(lat, lon)| (lat.0.clone(), lon.0.clone()))
+}
+
+fn is_h3_name(name: &str) -> bool {
+    let tokens = name_tokens(name);
+    let lower = name.to_lowercase();
+    lower.starts_with("h3")
+        || tokens.iter().any(|t| t.starts_with("h3") || t == "hex")
+        || (tokens.iter().any(|t| t == "cell") && tokens.iter().any(|t| t == "id" || t == "index"))
+}
+
+/// Whether a sample of the column values are valid H3 cells (string or integer indexes).
+pub fn is_h3_column(df: &DataFrame, name: &str) -> bool {
+    let Ok(column) = df.column(name) else {
+        return false;
+    };
+    let sample = column
+        .as_materialized_series()
+        .drop_nulls()
+        .head(Some(SAMPLE_SIZE));
+    if sample.is_empty() {
+        return false;
+    }
+
+    if sample.dtype().is_string() {
+        sample
+            .str()
+            .map(|s| s.iter().flatten().all(|v| CellIndex::from_str(v).is_ok()))
+            .unwrap_or(false)
+    } else if sample.dtype().is_integer() {
+        sample
+            .cast(&DataType::UInt64)
+            .ok()
+            .and_then(|s| {
+                s.u64()
+                    .ok()
+                    .map(|s| s.iter().flatten().all(|v| CellIndex::try_from(v).is_ok()))
+            })
+            .unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+fn detect_h3(df: &DataFrame) -> Option<String> {
+    let names: Vec<String> = df
+        .get_column_names()
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+    // Named columns first, then any string column whose values are H3 cells
+    names
+        .iter()
+        .filter(|name| is_h3_name(name))
+        .chain(names.iter().filter(|name| !is_h3_name(name)))
+        .find(|name| {
+            let dtype = df.column(name).map(|c| c.dtype().clone());
+            let named_or_string = is_h3_name(name) || matches!(dtype, Ok(DataType::String));
+            named_or_string && is_h3_column(df, name)
+        })
+        .cloned()
+}
+
+fn is_geometry_name(name: &str) -> bool {
+    name_tokens(name).iter().any(|t| {
+        matches!(
+            t.as_str(),
+            "geometry" | "geom" | "geog" | "geography" | "wkt" | "wkb" | "shape" | "footprint"
+        )
+    })
+}
+
+/// Whether a sample of the column values are WKB binary, or WKT or hex WKB strings.
+pub fn is_geometry_column(df: &DataFrame, name: &str) -> bool {
+    let Ok(column) = df.column(name) else {
+        return false;
+    };
+    let sample = column
+        .as_materialized_series()
+        .drop_nulls()
+        .head(Some(GEOMETRY_SAMPLE_SIZE));
+    if sample.is_empty() {
+        return false;
+    }
+
+    match sample.dtype() {
+        DataType::Binary => sample
+            .binary()
+            .map(|s| s.iter().flatten().all(|v| parse_wkb(v).is_some()))
+            .unwrap_or(false),
+        DataType::String => sample
+            .str()
+            .map(|s| s.iter().flatten().all(|v| parse_geometry_text(v).is_some()))
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+// Columns declared in GeoParquet metadata first, then named columns, then any with geometries
+fn detect_geometry(df: &DataFrame, declared: &[String]) -> Option<String> {
+    let names: Vec<String> = df
+        .get_column_names()
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+    declared
+        .iter()
+        .filter(|name| names.contains(name))
+        .chain(names.iter().filter(|name| is_geometry_name(name)))
+        .chain(names.iter().filter(|name| !is_geometry_name(name)))
+        .find(|name| is_geometry_column(df, name))
+        .cloned()
+}
+
+/// Detects latitude/longitude, H3 and geometry columns by name, type and value range.
+///
+/// Geometry columns declared in GeoParquet metadata are preferred.
+pub fn detect_map_columns(df: &DataFrame, declared_geometry: &[String]) -> MapColumns {
+    let lat_lon = detect_lat_lon(df);
+    MapColumns {
+        latitude: lat_lon.as_ref().map(|(lat, _)| lat.clone()),
+        longitude: lat_lon.map(|(_, lon)| lon),
+        h3cell: detect_h3(df),
+        geometry: detect_geometry(df, declared_geometry),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CELL: &str = "8928308280fffff";
+
+    #[test]
+    fn names_split_into_tokens() {
+        assert_eq!(name_tokens("pickup_lat"), vec!["pickup", "lat"]);
+        assert_eq!(
+            name_tokens("dropoffLongitude"),
+            vec!["dropoff", "longitude"]
+        );
+        assert_eq!(name_tokens("Start Lng-2"), vec!["start", "lng", "2"]);
+        assert_eq!(name_tokens("h3Cell9"), vec!["h3", "cell9"]);
+        assert!(name_tokens("__").is_empty());
+    }
+
+    #[test]
+    fn exact_names_outscore_tokens() {
+        assert_eq!(name_score("lat", Axis::Latitude), Some((25, String::new())));
+        assert_eq!(
+            name_score("pickup_latitude", Axis::Latitude),
+            Some((30, "pickup".to_string()))
+        );
+        assert_eq!(name_score("Y", Axis::Latitude), Some((15, String::new())));
+        assert_eq!(name_score("latency", Axis::Latitude), None);
+        assert_eq!(
+            name_score("lng", Axis::Longitude),
+            Some((35, String::new()))
+        );
+    }
+
+    #[test]
+    fn only_numeric_and_string_values_are_sampled() {
+        let df = df!(
+            "lat" => [Some(1.5), None, Some(-2.0)],
+            "text" => ["10", "20.5", "-3"],
+            "flag" => [true, false, true],
+            "empty" => [None::<f64>, None, None],
+        )
+        .unwrap();
+        assert_eq!(sample_values(&df, "lat"), Some(vec![1.5, -2.0]));
+        assert_eq!(sample_values(&df, "text"), Some(vec![10.0, 20.5, -3.0]));
+        assert_eq!(sample_values(&df, "flag"), None);
+        assert_eq!(sample_values(&df, "empty"), None);
+        assert_eq!(sample_values(&df, "missing"), None);
+    }
+
+    #[test]
+    fn values_must_be_within_axis_range() {
+        assert!(in_range(&[-90.0, 45.0, 90.0], Axis::Latitude));
+        assert!(!in_range(&[91.0], Axis::Latitude));
+        assert!(in_range(&[179.9, -180.0], Axis::Longitude));
+        assert!(!in_range(&[f64::NAN], Axis::Longitude));
+    }
+
+    #[test]
+    fn detects_pairs_sharing_prefix_within_range() {
+        let df = df!(
+            "x" => [500.0, 600.0],
+            "y" => [700.0, 800.0],
+            "pickup_lat" => [40.7, 40.8],
+            "pickup_lon" => [-74.0, -73.9],
+            "lat" => [1.0, 2.0],
+        )
+        .unwrap();
+        // x/y are out of range, and the stronger lat is not paired with pickup_lon
+        assert_eq!(
+            detect_lat_lon(&df),
+            Some(("pickup_lat".to_string(), "pickup_lon".to_string()))
+        );
+
+        let unnamed = df!("a" => [1.0], "b" => [2.0]).unwrap();
+        assert_eq!(detect_lat_lon(&unnamed), None);
+    }
+
+    #[test]
+    fn detects_h3_by_name_and_values() {
+        assert!(is_h3_name("h3_index"));
+        assert!(is_h3_name("cellId"));
+        assert!(is_h3_name("hex"));
+        assert!(!is_h3_name("cell"));
+
+        let cell = u64::from_str_radix(CELL, 16).unwrap();
+        let df = df!(
+            "name" => ["a", "b"],
+            "h3_res9" => [cell, cell],
+            "values" => [CELL, CELL],
+            "h3_bad" => [1u64, 2],
+        )
+        .unwrap();
+        assert!(is_h3_column(&df, "h3_res9"));
+        assert!(is_h3_column(&df, "values"));
+        assert!(!is_h3_column(&df, "h3_bad"));
+        assert!(!is_h3_column(&df, "name"));
+        assert_eq!(detect_h3(&df), Some("h3_res9".to_string()));
+
+        let strings_only = df!("id" => [1u64, 2], "cells" => [CELL, CELL]).unwrap();
+        assert_eq!(detect_h3(&strings_only), Some("cells".to_string()));
+    }
+
+    #[test]
+    fn map_columns_encode_round_trip() {
+        let columns = MapColumns {
+            latitude: Some("lat".to_string()),
+            longitude: Some("lon".to_string()),
+            h3cell: None,
+            geometry: Some("geom wkt".to_string()),
+        };
+        assert_eq!(MapColumns::decode(&columns.encode()), columns);
+        assert_eq!(MapColumns::decode(""), MapColumns::default());
+    }
+}
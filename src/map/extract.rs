@@ -0,0 +1,234 @@
+use h3o::CellIndex;
+use polars::prelude::*;
+use std::str::FromStr;
+
+use crate::map::geometry::{Geometry, parse_geometry_text, parse_wkb};
+
+// Values extracted from Columns with the DataFrame Row each came from
+pub struct Extracted<T> {
+    pub rows: Vec<usize>,
+    pub values: Vec<T>,
+    // Rows with a null value
+    pub missing: usize,
+    // Rows with a value which couldn't be read or was out of range
+    pub invalid: usize,
+}
+
+impl<T> Extracted<T> {
+    pub fn excluded(&self) -> usize {
+        self.missing + self.invalid
+    }
+}
+
+fn is_valid_coordinate(lat: f64, lon: f64) -> bool {
+    lat.is_finite() && lon.is_finite() && lat.abs() <= 90.0 && lon.abs() <= 180.0
+}
+
+/// Reads (lat, lon) pairs row by row, casting floats, integers, decimals or numeric strings.
+///
+/// Rows with a missing or out of range coordinate are dropped (and counted) as a whole,
+/// so every pair keeps its row identity.
+pub fn lat_lon_rows(
+    df: &DataFrame,
+    lat_column: &str,
+    lon_column: &str,
+) -> PolarsResult<Extracted<(f64, f64)>> {
+    let lat_column = df.column(lat_column)?;
+    let lon_column = df.column(lon_column)?;
+    let lat_values = lat_column.cast(&DataType::Float64)?;
+    let lon_values = lon_column.cast(&DataType::Float64)?;
+
+    let mut extracted = Extracted {
+        rows: Vec::new(),
+        values: Vec::new(),
+        missing: 0,
+        invalid: 0,
+    };
+
+    let lat_nulls = lat_column.as_materialized_series().is_null();
+    let lon_nulls = lon_column.as_materialized_series().is_null();
+    let rows = lat_values
+        .f64()?
+        .iter()
+        .zip(lon_values.f64()?.iter())
+        .zip(lat_nulls.iter().zip(lon_nulls.iter()));
+
+    for (row, ((lat, lon), (lat_null, lon_null))) in rows.enumerate() {
+        if lat_null == Some(true) || lon_null == Some(true) {
+            extracted.missing += 1;
+            continue;
+        }
+        match (lat, lon) {
+            (Some(lat), Some(lon)) if is_valid_coordinate(lat, lon) => {
+                extracted.rows.push(row);
+                extracted.values.push((lat, lon));
+            }
+            _ => extracted.invalid += 1,
+        }
+    }
+
+    Ok(extracted)
+}
+
+// Cells are Valid H3 Indexes (Some) or Invalid (None)
+fn parse_h3_index(index: u64) -> Option<String> {
+    CellIndex::try_from(index).ok().map(|cell| cell.to_string())
+}
+
+fn parse_h3_string(cell: &str) -> Option<String> {
+    CellIndex::from_str(cell).ok().map(|_| cell.to_string())
+}
+
+/// Reads H3 cells stored as strings or integer (u64) indexes, as their string representation.
+pub fn h3_cell_rows(df: &DataFrame, column_name: &str) -> PolarsResult<Extracted<String>> {
+    let column = df.column(column_name)?;
+
+    let cells: Vec<Option<Option<String>>> = if column.dtype().is_integer() {
+        let indexes = column.cast(&DataType::UInt64)?;
+        indexes
+            .u64()?
+            .iter()
+            .map(|index| index.map(parse_h3_index))
+            .collect()
+    } else {
+        let strings = column.cast(&DataType::String)?;
+        strings
+            .str()?
+            .iter()
+            .map(|cell| cell.map(parse_h3_string))
+            .collect()
+    };
+
+    let mut extracted = Extracted {
+        rows: Vec::new(),
+        values: Vec::new(),
+        missing: 0,
+        invalid: 0,
+    };
+    for (row, cell) in cells.into_iter().enumerate() {
+        match cell {
+            None => extracted.missing += 1,
+            Some(None) => extracted.invalid += 1,
+            Some(Some(cell)) => {
+                extracted.rows.push(row);
+                extracted.values.push(cell);
+            }
+        }
+    }
+
+    Ok(extracted)
+}
+
+/// Reads geometries stored as WKB binary, or as WKT or hex encoded WKB strings.
+///
+/// Multi-part geometries and collections are split into their parts, each keeping its row.
+/// Empty geometries count as missing.
+pub fn geometry_rows(df: &DataFrame, column_name: &str) -> PolarsResult<Extracted<Geometry>> {
+    let column = df.column(column_name)?;
+
+    let is_binary = matches!(column.dtype(), DataType::Binary | DataType::BinaryOffset);
+    let geometries: Vec<Option<Option<Vec<Geometry>>>> = if is_binary {
+        let bytes = column.cast(&DataType::Binary)?;
+        bytes
+            .as_materialized_series()
+            .binary()?
+            .iter()
+            .map(|bytes| bytes.map(parse_wkb))
+            .collect()
+    } else {
+        let strings = column.cast(&DataType::String)?;
+        strings
+            .str()?
+            .iter()
+            .map(|text| text.map(parse_geometry_text))
+            .collect()
+    };
+
+    let mut extracted = Extracted {
+        rows: Vec::new(),
+        values: Vec::new(),
+        missing: 0,
+        invalid: 0,
+    };
+    for (row, geometry) in geometries.into_iter().enumerate() {
+        match geometry {
+            None => extracted.missing += 1,
+            Some(None) => extracted.invalid += 1,
+            Some(Some(parts)) if parts.is_empty() => extracted.missing += 1,
+            Some(Some(parts)) => {
+                extracted.rows.extend(std::iter::repeat_n(row, parts.len()));
+                extracted.values.extend(parts);
+            }
+        }
+    }
+
+    Ok(extracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CELL: &str = "8928308280fffff";
+
+    #[test]
+    fn reads_u64_and_string_cells() {
+        let index = u64::from_str_radix(CELL, 16).unwrap();
+        let df = df!(
+            "index" => [index, 42],
+            "text" => [CELL, "not a cell"],
+        )
+        .unwrap();
+
+        let indexes = h3_cell_rows(&df, "index").unwrap();
+        assert_eq!(indexes.values, vec![CELL.to_string()]);
+        assert_eq!(indexes.invalid, 1);
+
+        let strings = h3_cell_rows(&df, "text").unwrap();
+        assert_eq!(strings.values, vec![CELL.to_string()]);
+        assert_eq!(strings.invalid, 1);
+    }
+
+    #[test]
+    fn reads_integer_coordinates() {
+        let df = df!("lat" => [52i32, -33], "lon" => [13i64, 151]).unwrap();
+        let extracted = lat_lon_rows(&df, "lat", "lon").unwrap();
+        assert_eq!(extracted.values, vec![(52.0, 13.0), (-33.0, 151.0)]);
+        assert_eq!(extracted.excluded(), 0);
+    }
+
+    #[test]
+    fn out_of_range_coordinates_are_invalid() {
+        let df = df!(
+            "lat" => [91.0, 45.0, -90.0, f64::NAN],
+            "lon" => [0.0, 181.0, 180.0, 0.0],
+        )
+        .unwrap();
+        let extracted = lat_lon_rows(&df, "lat", "lon").unwrap();
+        assert_eq!(extracted.values, vec![(-90.0, 180.0)]);
+        assert_eq!(extracted.invalid, 3);
+        assert_eq!(extracted.missing, 0);
+    }
+
+    #[test]
+    fn rows_stay_aligned_with_nulls_and_invalid_values() {
+        let df = df!(
+            "lat" => [Some(10.0), None, Some(95.0), Some(20.0), Some(30.0)],
+            "lon" => [Some(1.0), Some(2.0), Some(3.0), None, Some(4.0)],
+            "cell" => [Some(CELL), None, Some("bad"), Some(CELL), None],
+        )
+        .unwrap();
+
+        let coordinates = lat_lon_rows(&df, "lat", "lon").unwrap();
+        assert_eq!(coordinates.rows, vec![0, 4]);
+        assert_eq!(coordinates.values, vec![(10.0, 1.0), (30.0, 4.0)]);
+        assert_eq!(coordinates.missing, 2);
+        assert_eq!(coordinates.invalid, 1);
+
+        let cells = h3_cell_rows(&df, "cell").unwrap();
+        assert_eq!(cells.rows, vec![0, 3]);
+        assert_eq!(cells.missing, 2);
+        assert_eq!(cells.invalid, 1);
+        assert_eq!(cells.excluded(), 3);
+    }
+}
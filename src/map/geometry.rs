@@ -0,0 +1,725 @@
+use polars::prelude::{ParquetReader, SerReader};
+use serde_json::Value;
+use std::fs::File;
+use std::path::Path;
+
+// (lat, lon) Coordinate
+pub type Coord = (f64, f64);
+
+// Rings with more vertices are filled as a fan instead of being triangulated
+const MAX_TRIANGULATED_VERTICES: usize = 2_000;
+
+// Single Part of a Geometry Column value (multi-part geometries are split into their parts)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Point(Coord),
+    LineString(Vec<Coord>),
+    // Exterior ring then holes, with the triangles (into the exterior ring) filling it;
+    // holes are outlined but not cut out of the fill
+    Polygon {
+        rings: Vec<Vec<Coord>>,
+        triangles: Vec<[u32; 3]>,
+    },
+}
+
+impl Geometry {
+    fn polygon(mut rings: Vec<Vec<Coord>>) -> Option<Self> {
+        for ring in rings.iter_mut() {
+            // Rings repeat their first vertex at the end
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+        }
+        rings.retain(|ring| ring.len() >= 3);
+        let triangles = triangulate(rings.first()?);
+        Some(Geometry::Polygon { rings, triangles })
+    }
+
+    pub fn coords(&self) -> Box<dyn Iterator<Item = Coord> + '_> {
+        match self {
+            Geometry::Point(coord) => Box::new(std::iter::once(*coord)),
+            Geometry::LineString(coords) => Box::new(coords.iter().copied()),
+            Geometry::Polygon { rings, .. } => Box::new(rings.iter().flatten().copied()),
+        }
+    }
+
+    /// Center of the bounding box, standing for the geometry in spatial filters.
+    pub fn center(&self) -> Option<Coord> {
+        let (mut south, mut west) = (f64::INFINITY, f64::INFINITY);
+        let (mut north, mut east) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for (lat, lon) in self.coords() {
+            south = south.min(lat);
+            north = north.max(lat);
+            west = west.min(lon);
+            east = east.max(lon);
+        }
+        south
+            .is_finite()
+            .then(|| ((south + north) / 2.0, (west + east) / 2.0))
+    }
+}
+
+fn is_valid_coord((lat, lon): Coord) -> bool {
+    lat.is_finite() && lon.is_finite() && lat.abs() <= 90.0 && lon.abs() <= 180.0
+}
+
+// Twice the signed area of the ring, positive when counter-clockwise (lon as x, lat as y)
+fn signed_area(ring: &[Coord]) -> f64 {
+    let mut area = 0.0;
+    for i in 0..ring.len() {
+        let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+        area += a.1 * b.0 - b.1 * a.0;
+    }
+    area
+}
+
+fn cross(o: Coord, a: Coord, b: Coord) -> f64 {
+    (a.1 - o.1) * (b.0 - o.0) - (a.0 - o.0) * (b.1 - o.1)
+}
+
+fn triangle_contains(a: Coord, b: Coord, c: Coord, p: Coord) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+// Triangles filling the ring by ear clipping, or a fan for rings too large (or degenerate)
+fn triangulate(ring: &[Coord]) -> Vec<[u32; 3]> {
+    let fan = |indices: &[u32]| -> Vec<[u32; 3]> {
+        (1..indices.len().saturating_sub(1))
+            .map(|i| [indices[0], indices[i], indices[i + 1]])
+            .collect()
+    };
+    let mut indices: Vec<u32> = (0..ring.len() as u32).collect();
+    if ring.len() > MAX_TRIANGULATED_VERTICES {
+        return fan(&indices);
+    }
+    // Ears are found on the counter-clockwise ring
+    if signed_area(ring) < 0.0 {
+        indices.reverse();
+    }
+
+    let mut triangles = Vec::with_capacity(ring.len().saturating_sub(2));
+    while indices.len() > 3 {
+        let n = indices.len();
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = (indices[(i + n - 1) % n], indices[i], indices[(i + 1) % n]);
+            let (pa, pb, pc) = (ring[a as usize], ring[b as usize], ring[c as usize]);
+            cross(pa, pb, pc) > 0.0
+                && indices
+                    .iter()
+                    .filter(|&&j| j != a && j != b && j != c)
+                    .all(|&j| !triangle_contains(pa, pb, pc, ring[j as usize]))
+        });
+        let Some(i) = ear else {
+            triangles.extend(fan(&indices));
+            return triangles;
+        };
+        triangles.push([indices[(i + n - 1) % n], indices[i], indices[(i + 1) % n]]);
+        indices.remove(i);
+    }
+    triangles.extend(fan(&indices));
+    triangles
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(f64),
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            ',' => tokens.push(Token::Comma),
+            c if c.is_whitespace() => {}
+            c if c.is_ascii_alphabetic() => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphabetic()) {
+                    word.push(c.to_ascii_uppercase());
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+                continue;
+            }
+            c if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => {
+                let mut number = String::new();
+                while let Some(&c) = chars
+                    .peek()
+                    .filter(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+                {
+                    number.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Number(number.parse().ok()?));
+                continue;
+            }
+            _ => return None,
+        }
+        chars.next();
+    }
+    Some(tokens)
+}
+
+// Recursive descent parser of Well-Known Text
+struct WktParser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl WktParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, token: Token) -> Option<()> {
+        (self.advance()? == token).then_some(())
+    }
+
+    fn next_is(&mut self, token: Token) -> bool {
+        let found = self.peek() == Some(&token);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn next_is_empty(&mut self) -> bool {
+        self.next_is(Token::Word("EMPTY".to_string()))
+    }
+
+    // Comma separated items between parentheses
+    fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Option<T>) -> Option<Vec<T>> {
+        self.expect(Token::Open)?;
+        let mut items = vec![item(self)?];
+        while self.next_is(Token::Comma) {
+            items.push(item(self)?);
+        }
+        self.expect(Token::Close)?;
+        Some(items)
+    }
+
+    // x y with any z and m values, as (lat, lon)
+    fn coord(&mut self) -> Option<Coord> {
+        let mut values = Vec::with_capacity(4);
+        while let Some(Token::Number(value)) = self.peek() {
+            values.push(*value);
+            self.position += 1;
+        }
+        if values.len() < 2 {
+            return None;
+        }
+        let coord = (values[1], values[0]);
+        is_valid_coord(coord).then_some(coord)
+    }
+
+    fn coords(&mut self) -> Option<Vec<Coord>> {
+        if self.next_is_empty() {
+            return Some(Vec::new());
+        }
+        self.list(Self::coord)
+    }
+
+    fn rings(&mut self) -> Option<Vec<Vec<Coord>>> {
+        if self.next_is_empty() {
+            return Some(Vec::new());
+        }
+        self.list(Self::coords)
+    }
+
+    // Points of a MultiPoint may be wrapped in parentheses or not
+    fn multi_point_coord(&mut self) -> Option<Option<Coord>> {
+        if self.next_is_empty() {
+            return Some(None);
+        }
+        if self.next_is(Token::Open) {
+            let coord = self.coord()?;
+            self.expect(Token::Close)?;
+            return Some(Some(coord));
+        }
+        self.coord().map(Some)
+    }
+
+    fn geometry(&mut self, parts: &mut Vec<Geometry>) -> Option<()> {
+        let Token::Word(kind) = self.advance()? else {
+            return None;
+        };
+        // Dimension markers, e.g. POINT Z (1 2 3)
+        if let Some(Token::Word(word)) = self.peek()
+            && matches!(word.as_str(), "Z" | "M" | "ZM")
+        {
+            self.position += 1;
+        }
+
+        match kind.as_str() {
+            "POINT" => {
+                if !self.next_is_empty() {
+                    self.expect(Token::Open)?;
+                    parts.push(Geometry::Point(self.coord()?));
+                    self.expect(Token::Close)?;
+                }
+            }
+            "LINESTRING" => {
+                let coords = self.coords()?;
+                if coords.len() >= 2 {
+                    parts.push(Geometry::LineString(coords));
+                }
+            }
+            "POLYGON" => parts.extend(Geometry::polygon(self.rings()?)),
+            "MULTIPOINT" => {
+                if !self.next_is_empty() {
+                    let coords = self.list(Self::multi_point_coord)?;
+                    parts.extend(coords.into_iter().flatten().map(Geometry::Point));
+                }
+            }
+            "MULTILINESTRING" => {
+                if !self.next_is_empty() {
+                    let lines = self.list(Self::coords)?;
+                    parts.extend(
+                        lines
+                            .into_iter()
+                            .filter(|line| line.len() >= 2)
+                            .map(Geometry::LineString),
+                    );
+                }
+            }
+            "MULTIPOLYGON" => {
+                if !self.next_is_empty() {
+                    let polygons = self.list(Self::rings)?;
+                    parts.extend(polygons.into_iter().filter_map(Geometry::polygon));
+                }
+            }
+            "GEOMETRYCOLLECTION" => {
+                if !self.next_is_empty() {
+                    self.list(|parser| parser.geometry(parts))?;
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+/// Parses Well-Known Text (or EWKT with an SRID prefix) into its parts.
+pub fn parse_wkt(text: &str) -> Option<Vec<Geometry>> {
+    let text = text.trim();
+    let text = match text.split_once(';') {
+        Some((srid, rest)) if srid.to_uppercase().starts_with("SRID=") => rest,
+        _ => text,
+    };
+    let mut parser = WktParser {
+        tokens: tokenize(text)?,
+        position: 0,
+    };
+    let mut parts = Vec::new();
+    parser.geometry(&mut parts)?;
+    (parser.position == parser.tokens.len()).then_some(parts)
+}
+
+// Reader of Well-Known Binary, each geometry setting its own byte order
+struct WkbReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    little_endian: bool,
+}
+
+impl WkbReader<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.bytes.get(self.position..self.position + N)?;
+        self.position += N;
+        bytes.try_into().ok()
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        let bytes = self.take::<4>()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_f64(&mut self) -> Option<f64> {
+        let bytes = self.take::<8>()?;
+        Some(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    // Element count, checked against the remaining bytes before anything is allocated
+    fn read_count(&mut self, element_size: usize) -> Option<usize> {
+        let count = self.read_u32()? as usize;
+        let remaining = self.bytes.len() - self.position;
+        (count.checked_mul(element_size)? <= remaining).then_some(count)
+    }
+
+    // (lat, lon), skipping z and m values; None for NaN (empty) points
+    fn coord(&mut self, dimensions: usize) -> Option<Option<Coord>> {
+        let x = self.read_f64()?;
+        let y = self.read_f64()?;
+        for _ in 2..dimensions {
+            self.read_f64()?;
+        }
+        if x.is_nan() && y.is_nan() {
+            return Some(None);
+        }
+        let coord = (y, x);
+        is_valid_coord(coord).then_some(Some(coord))
+    }
+
+    fn coords(&mut self, dimensions: usize) -> Option<Vec<Coord>> {
+        let count = self.read_count(dimensions * 8)?;
+        (0..count)
+            .map(|_| self.coord(dimensions)?)
+            .collect::<Option<Vec<_>>>()
+    }
+
+    fn geometry(&mut self, parts: &mut Vec<Geometry>) -> Option<()> {
+        self.little_endian = match self.take::<1>()?[0] {
+            0 => false,
+            1 => true,
+            _ => return None,
+        };
+        let kind = self.read_u32()?;
+
+        // EWKB flags, then ISO dimensions (1000 Z, 2000 M, 3000 ZM)
+        let (mut has_z, mut has_m) = (kind & 0x8000_0000 != 0, kind & 0x4000_0000 != 0);
+        if kind & 0x2000_0000 != 0 {
+            self.read_u32()?;
+        }
+        let kind = kind & 0x0fff_ffff;
+        match kind / 1000 {
+            0 => {}
+            1 => has_z = true,
+            2 => has_m = true,
+            3 => (has_z, has_m) = (true, true),
+            _ => return None,
+        }
+        let dimensions = 2 + has_z as usize + has_m as usize;
+
+        match kind % 1000 {
+            1 => parts.extend(self.coord(dimensions)?.map(Geometry::Point)),
+            2 => {
+                let coords = self.coords(dimensions)?;
+                if coords.len() >= 2 {
+                    parts.push(Geometry::LineString(coords));
+                }
+            }
+            3 => {
+                let count = self.read_count(4)?;
+                let rings = (0..count)
+                    .map(|_| self.coords(dimensions))
+                    .collect::<Option<Vec<_>>>()?;
+                parts.extend(Geometry::polygon(rings));
+            }
+            // Multi geometries and collections hold complete geometries
+            4..=7 => {
+                let count = self.read_count(5)?;
+                for _ in 0..count {
+                    self.geometry(parts)?;
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+/// Parses Well-Known Binary (ISO or EWKB) into its parts.
+pub fn parse_wkb(bytes: &[u8]) -> Option<Vec<Geometry>> {
+    let mut reader = WkbReader {
+        bytes,
+        position: 0,
+        little_endian: true,
+    };
+    let mut parts = Vec::new();
+    reader.geometry(&mut parts)?;
+    (reader.position == bytes.len()).then_some(parts)
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Parses WKT, or WKB encoded as hex (as PostGIS outputs it).
+pub fn parse_geometry_text(text: &str) -> Option<Vec<Geometry>> {
+    let text = text.trim();
+    if text.starts_with("00") || text.starts_with("01") {
+        return parse_wkb(&decode_hex(text)?);
+    }
+    parse_wkt(text)
+}
+
+// Primary column first, then the other declared geometry columns
+fn geo_columns(json: &str) -> Vec<String> {
+    let Ok(metadata) = serde_json::from_str::<Value>(json) else {
+        return Vec::new();
+    };
+    let primary = metadata.get("primary_column").and_then(Value::as_str);
+    let declared = metadata
+        .get("columns")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|columns| columns.keys().map(String::as_str));
+
+    let mut columns: Vec<String> = Vec::new();
+    for column in primary.into_iter().chain(declared) {
+        if !columns.iter().any(|c| c == column) {
+            columns.push(column.to_string());
+        }
+    }
+    columns
+}
+
+/// Geometry columns declared in the GeoParquet `geo` metadata of the file, primary first.
+pub fn geoparquet_columns(path: &Path) -> Vec<String> {
+    let geo_metadata = || -> Option<String> {
+        let mut reader = ParquetReader::new(File::open(path).ok()?);
+        let metadata = reader.get_metadata().ok()?;
+        metadata
+            .key_value_metadata()
+            .as_ref()?
+            .iter()
+            .find(|entry| entry.key == "geo")?
+            .value
+            .clone()
+    };
+    geo_metadata()
+        .map(|json| geo_columns(&json))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(lat: f64, lon: f64) -> Geometry {
+        Geometry::Point((lat, lon))
+    }
+
+    // Little endian WKB header of the geometry type
+    fn wkb_header(kind: u32) -> Vec<u8> {
+        let mut bytes = vec![1];
+        bytes.extend(kind.to_le_bytes());
+        bytes
+    }
+
+    fn push_coords(bytes: &mut Vec<u8>, values: &[f64]) {
+        for value in values {
+            bytes.extend(value.to_le_bytes());
+        }
+    }
+
+    #[test]
+    fn parses_wkt_point_as_lat_lon() {
+        assert_eq!(parse_wkt("POINT (4 52)"), Some(vec![point(52.0, 4.0)]));
+    }
+
+    #[test]
+    fn parses_ewkt_with_srid() {
+        assert_eq!(
+            parse_wkt("SRID=4326;POINT(4 52)"),
+            Some(vec![point(52.0, 4.0)])
+        );
+    }
+
+    #[test]
+    fn skips_z_and_m_values_in_wkt() {
+        let expected = Some(vec![point(52.0, 4.0)]);
+        assert_eq!(parse_wkt("POINT Z (4 52 10)"), expected);
+        assert_eq!(parse_wkt("POINT M (4 52 7)"), expected);
+        assert_eq!(parse_wkt("POINT ZM (4 52 10 7)"), expected);
+        assert_eq!(parse_wkt("POINT (4 52 10)"), expected);
+        assert_eq!(
+            parse_wkt("LINESTRING ZM (0 0 1 2, 1 1 3 4)"),
+            Some(vec![Geometry::LineString(vec![(0.0, 0.0), (1.0, 1.0)])])
+        );
+    }
+
+    #[test]
+    fn parses_empty_wkt_without_parts() {
+        for text in [
+            "POINT EMPTY",
+            "LINESTRING EMPTY",
+            "POLYGON EMPTY",
+            "MULTIPOINT EMPTY",
+            "MULTIPOLYGON EMPTY",
+            "GEOMETRYCOLLECTION EMPTY",
+        ] {
+            assert_eq!(parse_wkt(text), Some(Vec::new()), "{}", text);
+        }
+    }
+
+    #[test]
+    fn splits_nested_geometry_collections() {
+        let parts = parse_wkt(
+            "GEOMETRYCOLLECTION (POINT (1 2), \
+             GEOMETRYCOLLECTION (LINESTRING (0 0, 1 1), MULTIPOINT ((3 4), 5 6)))",
+        )
+        .unwrap();
+        assert_eq!(
+            parts,
+            vec![
+                point(2.0, 1.0),
+                Geometry::LineString(vec![(0.0, 0.0), (1.0, 1.0)]),
+                point(4.0, 3.0),
+                point(6.0, 5.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_wkt() {
+        assert_eq!(parse_wkt("POINT (1 2"), None);
+        assert_eq!(parse_wkt("POINT (1 2) trailing"), None);
+        assert_eq!(parse_wkt("CIRCLE (1 2)"), None);
+        assert_eq!(parse_wkt("POINT (200 2)"), None);
+    }
+
+    #[test]
+    fn parses_little_endian_wkb_point() {
+        let mut bytes = wkb_header(1);
+        push_coords(&mut bytes, &[4.0, 52.0]);
+        assert_eq!(parse_wkb(&bytes), Some(vec![point(52.0, 4.0)]));
+    }
+
+    #[test]
+    fn parses_big_endian_wkb() {
+        let mut bytes = vec![0];
+        bytes.extend(2u32.to_be_bytes());
+        bytes.extend(2u32.to_be_bytes());
+        for value in [0.0f64, 0.0, 1.0, 1.0] {
+            bytes.extend(value.to_be_bytes());
+        }
+        assert_eq!(
+            parse_wkb(&bytes),
+            Some(vec![Geometry::LineString(vec![(0.0, 0.0), (1.0, 1.0)])])
+        );
+    }
+
+    #[test]
+    fn parses_ewkb_with_srid_and_z() {
+        // Point with the EWKB Z and SRID flags
+        let mut bytes = wkb_header(0x8000_0000 | 0x2000_0000 | 1);
+        bytes.extend(4326u32.to_le_bytes());
+        push_coords(&mut bytes, &[4.0, 52.0, 10.0]);
+        assert_eq!(parse_wkb(&bytes), Some(vec![point(52.0, 4.0)]));
+    }
+
+    #[test]
+    fn parses_iso_wkb_zm() {
+        let mut bytes = wkb_header(3001);
+        push_coords(&mut bytes, &[4.0, 52.0, 10.0, 7.0]);
+        assert_eq!(parse_wkb(&bytes), Some(vec![point(52.0, 4.0)]));
+    }
+
+    #[test]
+    fn parses_empty_wkb_point_without_parts() {
+        let mut bytes = wkb_header(1);
+        push_coords(&mut bytes, &[f64::NAN, f64::NAN]);
+        assert_eq!(parse_wkb(&bytes), Some(Vec::new()));
+    }
+
+    #[test]
+    fn rejects_truncated_wkb() {
+        let mut bytes = wkb_header(2);
+        bytes.extend(3u32.to_le_bytes());
+        push_coords(&mut bytes, &[0.0, 0.0, 1.0, 1.0, 2.0, 2.0]);
+        assert!(parse_wkb(&bytes).is_some());
+        for length in 0..bytes.len() {
+            assert_eq!(parse_wkb(&bytes[..length]), None, "{} bytes", length);
+        }
+    }
+
+    #[test]
+    fn rejects_wkb_counts_beyond_the_input() {
+        let mut bytes = wkb_header(2);
+        bytes.extend(u32::MAX.to_le_bytes());
+        assert_eq!(parse_wkb(&bytes), None);
+    }
+
+    #[test]
+    fn parses_hex_encoded_wkb_text() {
+        let mut bytes = wkb_header(1);
+        push_coords(&mut bytes, &[4.0, 52.0]);
+        let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+        assert_eq!(parse_geometry_text(&hex), Some(vec![point(52.0, 4.0)]));
+    }
+
+    // Sum of the triangle areas, twice the area like `signed_area`
+    fn triangles_area(ring: &[Coord], triangles: &[[u32; 3]]) -> f64 {
+        triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.map(|i| ring[i as usize]);
+                cross(a, b, c).abs()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn triangulates_convex_ring() {
+        let ring = [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)];
+        let triangles = triangulate(&ring);
+        assert_eq!(triangles.len(), 2);
+        assert!((triangles_area(&ring, &triangles) - signed_area(&ring).abs()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn triangulates_concave_ring_inside_its_outline() {
+        // U shape, clockwise, whose notch must stay unfilled
+        let ring = [
+            (0.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 2.0),
+            (3.0, 2.0),
+            (3.0, 3.0),
+            (0.0, 3.0),
+        ];
+        for ring in [ring.to_vec(), ring.iter().rev().copied().collect()] {
+            let triangles = triangulate(&ring);
+            assert_eq!(triangles.len(), ring.len() - 2);
+            assert!((triangles_area(&ring, &triangles) - signed_area(&ring).abs()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn closes_polygon_rings_and_drops_degenerate_holes() {
+        let parts = parse_wkt("POLYGON ((0 0, 1 0, 1 1, 0 1, 0 0), (0.2 0.2, 0.3 0.3, 0.2 0.2))");
+        let Some([Geometry::Polygon { rings, triangles }]) = parts.as_deref() else {
+            panic!("expected a polygon, got {:?}", parts);
+        };
+        assert_eq!(rings.len(), 1);
+        assert_eq!(rings[0].len(), 4);
+        assert_eq!(triangles.len(), 2);
+    }
+
+    #[test]
+    fn reads_geoparquet_columns_primary_first() {
+        let json = r#"{"version": "1.0.0", "primary_column": "geom",
+            "columns": {"boundary": {"encoding": "WKB"}, "geom": {"encoding": "WKB"}}}"#;
+        assert_eq!(geo_columns(json), vec!["geom", "boundary"]);
+        assert!(geo_columns("not json").is_empty());
+    }
+}
@@ -0,0 +1,366 @@
+use h3o::{CellIndex, LatLng, Resolution};
+use polars::prelude::*;
+use std::fmt;
+use std::str::FromStr;
+
+// Most rows Uncompact may expand the data to
+const MAX_UNCOMPACT_ROWS: u64 = 10_000_000;
+
+// Column Operation on H3 Cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum H3Operation {
+    Parent,
+    Resolution,
+    Centroid,
+    Area,
+    Compact,
+    Uncompact,
+}
+
+impl H3Operation {
+    pub const ALL: [H3Operation; 6] = [
+        H3Operation::Parent,
+        H3Operation::Resolution,
+        H3Operation::Centroid,
+        H3Operation::Area,
+        H3Operation::Compact,
+        H3Operation::Uncompact,
+    ];
+
+    /// Whether the operation takes a target resolution.
+    pub fn uses_resolution(&self) -> bool {
+        matches!(self, H3Operation::Parent | H3Operation::Uncompact)
+    }
+
+    /// Whether the result has different rows than the input (instead of added columns).
+    pub fn changes_rows(&self) -> bool {
+        matches!(self, H3Operation::Compact | H3Operation::Uncompact)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            H3Operation::Parent => "Adds the parent cell at a coarser resolution",
+            H3Operation::Resolution => "Adds the cell resolution",
+            H3Operation::Centroid => "Adds the cell centroid latitude and longitude",
+            H3Operation::Area => "Adds the cell area in km²",
+            H3Operation::Compact => "Shows the compacted set of the filtered cells",
+            H3Operation::Uncompact => {
+                "Shows each filtered row expanded into its child cells at a finer resolution"
+            }
+        }
+    }
+}
+
+impl fmt::Display for H3Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            H3Operation::Parent => write!(f, "Parent Cell"),
+            H3Operation::Resolution => write!(f, "Resolution"),
+            H3Operation::Centroid => write!(f, "Centroid"),
+            H3Operation::Area => write!(f, "Area"),
+            H3Operation::Compact => write!(f, "Compact"),
+            H3Operation::Uncompact => write!(f, "Uncompact"),
+        }
+    }
+}
+
+// Compact/Uncompact result shown in place of the filtered rows, keeping the loaded data
+#[derive(Debug, Clone, PartialEq)]
+pub struct H3View {
+    pub column_name: String,
+    pub operation: H3Operation,
+    pub resolution: u8,
+}
+
+// Cell of each row, None when missing or invalid
+fn row_cells(df: &DataFrame, column_name: &str) -> PolarsResult<Vec<Option<CellIndex>>> {
+    let column = df.column(column_name)?;
+    if column.dtype().is_integer() {
+        let indexes = column.cast(&DataType::UInt64)?;
+        Ok(indexes
+            .u64()?
+            .iter()
+            .map(|index| index.and_then(|index| CellIndex::try_from(index).ok()))
+            .collect())
+    } else {
+        let strings = column.cast(&DataType::String)?;
+        Ok(strings
+            .str()?
+            .iter()
+            .map(|cell| cell.and_then(|cell| CellIndex::from_str(cell).ok()))
+            .collect())
+    }
+}
+
+fn cell_strings(cells: impl IntoIterator<Item = Option<CellIndex>>) -> Vec<Option<String>> {
+    cells
+        .into_iter()
+        .map(|cell| cell.map(|cell| cell.to_string()))
+        .collect()
+}
+
+fn parse_resolution(resolution: u8) -> PolarsResult<Resolution> {
+    Resolution::try_from(resolution)
+        .map_err(|e| polars_err!(InvalidOperation: "resolution {}: {}", resolution, e))
+}
+
+/// Names of the columns the operation adds for the H3 column.
+pub fn output_column_names(
+    column_name: &str,
+    operation: H3Operation,
+    resolution: u8,
+) -> Vec<String> {
+    match operation {
+        H3Operation::Parent => vec![format!("{}_parent_{}", column_name, resolution)],
+        H3Operation::Resolution => vec![format!("{}_resolution", column_name)],
+        H3Operation::Centroid => vec![
+            format!("{}_lat", column_name),
+            format!("{}_lon", column_name),
+        ],
+        H3Operation::Area => vec![format!("{}_area_km2", column_name)],
+        H3Operation::Compact => vec![format!("{}_compact", column_name)],
+        H3Operation::Uncompact => vec![format!("{}_{}", column_name, resolution)],
+    }
+}
+
+/// Applies the H3 operation to the cells of the column, returning the resulting DataFrame.
+///
+/// Rows with a missing or invalid cell get nulls. Compact returns only the compacted cells,
+/// and Uncompact repeats each row once per child cell.
+pub fn apply_h3_operation(
+    df: &DataFrame,
+    column_name: &str,
+    operation: H3Operation,
+    resolution: u8,
+) -> PolarsResult<DataFrame> {
+    let names = output_column_names(column_name, operation, resolution);
+    for name in &names {
+        polars_ensure!(
+            df.column(name).is_err(),
+            Duplicate: "column '{}' already exists", name
+        );
+    }
+    let cells = row_cells(df, column_name)?;
+    let name = names[0].as_str();
+
+    let mut result = df.clone();
+    match operation {
+        H3Operation::Parent => {
+            let resolution = parse_resolution(resolution)?;
+            let parents = cells
+                .iter()
+                .map(|cell| cell.and_then(|cell| cell.parent(resolution)));
+            result.with_column(Column::new(name.into(), cell_strings(parents)))?;
+        }
+        H3Operation::Resolution => {
+            let resolutions: Vec<Option<u8>> = cells
+                .iter()
+                .map(|cell| cell.map(|cell| u8::from(cell.resolution())))
+                .collect();
+            result.with_column(Column::new(name.into(), resolutions))?;
+        }
+        H3Operation::Centroid => {
+            let centroids: Vec<Option<LatLng>> =
+                cells.iter().map(|cell| cell.map(LatLng::from)).collect();
+            let lats: Vec<Option<f64>> = centroids.iter().map(|c| c.map(|c| c.lat())).collect();
+            let lons: Vec<Option<f64>> = centroids.iter().map(|c| c.map(|c| c.lng())).collect();
+            result.with_column(Column::new(name.into(), lats))?;
+            result.with_column(Column::new(names[1].as_str().into(), lons))?;
+        }
+        H3Operation::Area => {
+            let areas: Vec<Option<f64>> = cells
+                .iter()
+                .map(|cell| cell.map(|cell| cell.area_km2()))
+                .collect();
+            result.with_column(Column::new(name.into(), areas))?;
+        }
+        H3Operation::Compact => {
+            let mut unique: Vec<CellIndex> = cells.into_iter().flatten().collect();
+            unique.sort_unstable();
+            unique.dedup();
+            CellIndex::compact(&mut unique)
+                .map_err(|e| polars_err!(ComputeError: "cannot compact cells: {}", e))?;
+            let compacted: Vec<String> = unique.iter().map(|cell| cell.to_string()).collect();
+            result = Column::new(name.into(), compacted).into_frame();
+        }
+        H3Operation::Uncompact => {
+            let resolution = parse_resolution(resolution)?;
+            let total: u64 = cells
+                .iter()
+                .map(|cell| cell.map_or(1, |cell| cell.children_count(resolution).max(1)))
+                .sum();
+            polars_ensure!(
+                total <= MAX_UNCOMPACT_ROWS,
+                InvalidOperation: "uncompacting would create {} rows (at most {})",
+                total, MAX_UNCOMPACT_ROWS
+            );
+            let mut rows: Vec<IdxSize> = Vec::new();
+            let mut children: Vec<Option<String>> = Vec::new();
+            for (row, cell) in cells.iter().enumerate() {
+                let before = children.len();
+                if let Some(cell) = cell {
+                    children.extend(cell.children(resolution).map(|c| Some(c.to_string())));
+                }
+                // Rows without children (invalid or finer cells) are kept once with a null
+                if children.len() == before {
+                    children.push(None);
+                }
+                rows.extend(std::iter::repeat_n(row as IdxSize, children.len() - before));
+            }
+            result = df.take(&IdxCa::from_vec("rows".into(), rows))?;
+            result.with_column(Column::new(name.into(), children))?;
+        }
+    }
+    Ok(result)
+}
+
+/// Cells within k steps of the cell (including itself).
+pub fn k_ring(cell: &str, k: u32) -> Vec<String> {
+    let Ok(cell) = CellIndex::from_str(cell) else {
+        return Vec::new();
+    };
+    cell.grid_disk::<Vec<_>>(k)
+        .into_iter()
+        .map(|cell| cell.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Resolution 9 cell in San Francisco, used by the H3 documentation
+    const CELL: &str = "8928308280fffff";
+    const PARENT: &str = "8828308281fffff";
+
+    fn cells(values: &[Option<&str>]) -> DataFrame {
+        df!("h3" => values).unwrap()
+    }
+
+    fn strings(df: &DataFrame, name: &str) -> Vec<Option<String>> {
+        df.column(name)
+            .unwrap()
+            .str()
+            .unwrap()
+            .iter()
+            .map(|s| s.map(str::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn parent_and_resolution_of_cell() {
+        let df = cells(&[Some(CELL), None, Some("bad")]);
+        let parents = apply_h3_operation(&df, "h3", H3Operation::Parent, 8).unwrap();
+        assert_eq!(
+            strings(&parents, "h3_parent_8"),
+            vec![Some(PARENT.to_string()), None, None]
+        );
+        let parents = apply_h3_operation(&df, "h3", H3Operation::Parent, 7).unwrap();
+        assert_eq!(
+            strings(&parents, "h3_parent_7")[0].as_deref(),
+            Some("872830828ffffff")
+        );
+
+        let resolutions = apply_h3_operation(&df, "h3", H3Operation::Resolution, 0).unwrap();
+        let resolutions: Vec<Option<u8>> = resolutions
+            .column("h3_resolution")
+            .unwrap()
+            .u8()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(resolutions, vec![Some(9), None, None]);
+    }
+
+    #[test]
+    fn centroid_and_area_of_cell() {
+        let index = u64::from_str_radix(CELL, 16).unwrap();
+        let df = df!("h3" => [index]).unwrap();
+
+        let centroid = apply_h3_operation(&df, "h3", H3Operation::Centroid, 0).unwrap();
+        let lat = centroid
+            .column("h3_lat")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+            .unwrap();
+        let lon = centroid
+            .column("h3_lon")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+            .unwrap();
+        assert!((lat - 37.776_702).abs() < 1e-6, "{lat}");
+        assert!((lon + 122.418_459).abs() < 1e-6, "{lon}");
+
+        let area = apply_h3_operation(&df, "h3", H3Operation::Area, 0).unwrap();
+        let area = area
+            .column("h3_area_km2")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+            .unwrap();
+        // Resolution 9 cells average 0.105 km²
+        assert!((0.09..0.12).contains(&area), "{area}");
+    }
+
+    #[test]
+    fn k_ring_of_cell() {
+        assert_eq!(k_ring(CELL, 0), vec![CELL.to_string()]);
+        let mut ring = k_ring(CELL, 1);
+        ring.sort();
+        assert_eq!(
+            ring,
+            vec![
+                "89283082803ffff",
+                "89283082807ffff",
+                "8928308280bffff",
+                "8928308280fffff",
+                "8928308283bffff",
+                "89283082873ffff",
+                "89283082877ffff",
+            ]
+        );
+        assert_eq!(k_ring(CELL, 2).len(), 19);
+        assert!(k_ring("bad", 1).is_empty());
+    }
+
+    #[test]
+    fn compact_and_uncompact_round_trip() {
+        let uncompacted = apply_h3_operation(
+            &cells(&[Some(PARENT), None]),
+            "h3",
+            H3Operation::Uncompact,
+            9,
+        )
+        .unwrap();
+        let children = strings(&uncompacted, "h3_9");
+        assert_eq!(children.len(), 7 + 1);
+        assert!(children.contains(&Some(CELL.to_string())));
+        assert_eq!(children.last(), Some(&None));
+
+        // Children compact back into their parent, other cells are kept
+        let mut values: Vec<Option<&str>> = children.iter().map(|c| c.as_deref()).collect();
+        values.push(Some("8928308283bffff"));
+        let compacted = apply_h3_operation(&cells(&values), "h3", H3Operation::Compact, 0).unwrap();
+        let mut compacted = strings(&compacted, "h3_compact");
+        compacted.sort();
+        assert_eq!(
+            compacted,
+            vec![
+                Some(PARENT.to_string()),
+                Some("8928308283bffff".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn existing_output_column_is_refused() {
+        let df = df!("h3" => [CELL], "h3_resolution" => [1]).unwrap();
+        assert!(apply_h3_operation(&df, "h3", H3Operation::Resolution, 0).is_err());
+        assert!(apply_h3_operation(&df, "h3", H3Operation::Parent, 16).is_err());
+    }
+}
@@ -0,0 +1,218 @@
+use ::eframe::egui::{Color32, Mesh, Rect, Response, Shape, Ui, pos2};
+use walkers::{MapMemory, Plugin, Projector};
+
+use crate::map::cluster::PointIndex;
+use crate::map::colormap::Colormap;
+
+// Screen size of the density grid cells
+const HEATMAP_BIN: f32 = 4.0;
+const HEATMAP_OPACITY: f32 = 0.8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeatmapStyle {
+    // Kernel radius in pixels
+    pub radius: f32,
+    pub intensity: f32,
+    pub colormap: Colormap,
+    pub weight_column: Option<String>,
+}
+
+impl Default for HeatmapStyle {
+    fn default() -> Self {
+        Self {
+            radius: 20.0,
+            intensity: 1.0,
+            colormap: Colormap::Magma,
+            weight_column: None,
+        }
+    }
+}
+
+// View the densities were computed for: rect, center (lon, lat), zoom and radius
+type HeatmapView = (Rect, (f64, f64), f64, f32);
+
+// Densities of the last view, recomputed only when the view changes
+#[derive(Default)]
+pub struct HeatmapCache {
+    view: Option<HeatmapView>,
+    columns: usize,
+    densities: Vec<f32>,
+}
+
+impl HeatmapCache {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// Gaussian kernel weights for offsets -n..=n bins
+fn kernel(radius_bins: f32) -> Vec<f32> {
+    let n = radius_bins.ceil().max(1.0) as i32;
+    let sigma = (radius_bins / 2.0).max(0.5);
+    (-n..=n)
+        .map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect()
+}
+
+// Separable blur of the grid with the kernel, dropping what spreads past the edges
+fn blur(grid: &[f32], columns: usize, rows: usize, kernel: &[f32]) -> Vec<f32> {
+    let n = (kernel.len() / 2) as isize;
+    let pass = |source: &[f32], horizontal: bool| {
+        let mut target = vec![0.0; source.len()];
+        for y in 0..rows {
+            for x in 0..columns {
+                let value = source[y * columns + x];
+                if value == 0.0 {
+                    continue;
+                }
+                for (k, weight) in kernel.iter().enumerate() {
+                    let offset = k as isize - n;
+                    let (tx, ty) = if horizontal {
+                        (x as isize + offset, y as isize)
+                    } else {
+                        (x as isize, y as isize + offset)
+                    };
+                    if tx < 0 || ty < 0 || tx >= columns as isize || ty >= rows as isize {
+                        continue;
+                    }
+                    target[ty as usize * columns + tx as usize] += value * weight;
+                }
+            }
+        }
+        target
+    };
+    pass(&pass(grid, true), false)
+}
+
+// Kernel Density Heatmap Plugin of the Point Plot
+pub struct HeatmapPlot<'a> {
+    index: &'a PointIndex,
+    // DataFrame Row of each Point
+    rows: &'a [usize],
+    // Per Row Weights (every point weighs 1 when empty)
+    row_weights: &'a [f64],
+    style: &'a HeatmapStyle,
+    cache: &'a mut HeatmapCache,
+}
+
+impl<'a> HeatmapPlot<'a> {
+    pub fn new(
+        index: &'a PointIndex,
+        rows: &'a [usize],
+        row_weights: &'a [f64],
+        style: &'a HeatmapStyle,
+        cache: &'a mut HeatmapCache,
+    ) -> Self {
+        Self {
+            index,
+            rows,
+            row_weights,
+            style,
+            cache,
+        }
+    }
+
+    // Densities over the view expanded by the radius, so points just outside still count
+    fn compute_densities(&mut self, projector: &Projector, rect: Rect) {
+        let area = rect.expand(self.style.radius);
+        let columns = (area.width() / HEATMAP_BIN).ceil() as usize + 1;
+        let rows = (area.height() / HEATMAP_BIN).ceil() as usize + 1;
+        let mut grid = vec![0.0f32; columns * rows];
+
+        // Weighted points are visited individually, otherwise small grid cells are merged
+        let detail = if self.row_weights.is_empty() {
+            HEATMAP_BIN
+        } else {
+            0.0
+        };
+        self.index.visit(
+            projector,
+            rect,
+            self.style.radius,
+            detail,
+            |pos, point, count| {
+                let weight = match point {
+                    Some(point) if !self.row_weights.is_empty() => self
+                        .rows
+                        .get(point)
+                        .and_then(|&row| self.row_weights.get(row))
+                        .copied()
+                        .unwrap_or(0.0),
+                    _ => count,
+                };
+                let x = ((pos.x - area.min.x) / HEATMAP_BIN).round();
+                let y = ((pos.y - area.min.y) / HEATMAP_BIN).round();
+                let x = x.clamp(0.0, (columns - 1) as f32) as usize;
+                let y = y.clamp(0.0, (rows - 1) as f32) as usize;
+                grid[y * columns + x] += weight as f32;
+            },
+        );
+
+        let kernel = kernel(self.style.radius / HEATMAP_BIN);
+        self.cache.densities = blur(&grid, columns, rows, &kernel);
+        self.cache.columns = columns;
+    }
+}
+
+impl Plugin for HeatmapPlot<'_> {
+    fn run(
+        mut self: Box<Self>,
+        ui: &mut Ui,
+        response: &Response,
+        projector: &Projector,
+        map_memory: &MapMemory,
+    ) {
+        let rect = response.rect;
+        let center = projector.unproject(rect.center().to_vec2());
+        let view = (
+            rect,
+            (center.x(), center.y()),
+            map_memory.zoom(),
+            self.style.radius,
+        );
+        if self.cache.view != Some(view) {
+            self.compute_densities(projector, rect);
+            self.cache.view = Some(view);
+        }
+
+        let area = rect.expand(self.style.radius);
+        let densities = &self.cache.densities;
+        let columns = self.cache.columns;
+        let max = densities.iter().copied().fold(0.0f32, f32::max);
+        if max <= 0.0 || columns == 0 {
+            return;
+        }
+
+        // Grid vertices colored by density, interpolated across each cell
+        let mut mesh = Mesh::default();
+        for (i, density) in densities.iter().enumerate() {
+            let t = (density / max * self.style.intensity).min(1.0);
+            let color = if t > 0.0 {
+                self.style
+                    .colormap
+                    .color(t as f64)
+                    .gamma_multiply(t.sqrt() * HEATMAP_OPACITY)
+            } else {
+                Color32::TRANSPARENT
+            };
+            let (x, y) = (i % columns, i / columns);
+            mesh.colored_vertex(
+                pos2(
+                    area.min.x + x as f32 * HEATMAP_BIN,
+                    area.min.y + y as f32 * HEATMAP_BIN,
+                ),
+                color,
+            );
+        }
+        let rows = densities.len() / columns;
+        for y in 0..rows.saturating_sub(1) {
+            for x in 0..columns - 1 {
+                let top_left = (y * columns + x) as u32;
+                let bottom_left = top_left + columns as u32;
+                mesh.add_triangle(top_left, top_left + 1, bottom_left + 1);
+                mesh.add_triangle(top_left, bottom_left + 1, bottom_left);
+            }
+        }
+        ui.painter().with_clip_rect(rect).add(Shape::mesh(mesh));
+    }
+}
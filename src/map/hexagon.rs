@@ -1,12 +1,35 @@
-use ::eframe::egui::{Color32, Response, Stroke, Ui};
+use ::eframe::egui::{Color32, Pos2, Rect, Response, Shape, Stroke, Ui};
 use h3o::{CellIndex, LatLng};
+use std::collections::BTreeSet;
 use std::str::FromStr;
 use walkers::{MapMemory, Plugin, Position, Projector};
 use walkers_extras::Place;
 
+use crate::map::interaction::{HIGHLIGHT_COLOR, HoveredFeature, polygon_contains};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HexagonStyle {
+    pub fill_color: Color32,
+    pub fill_opacity: f32,
+    pub stroke_color: Color32,
+    pub stroke_width: f32,
+}
+
+impl Default for HexagonStyle {
+    fn default() -> Self {
+        Self {
+            fill_color: Color32::BLUE,
+            fill_opacity: 0.4,
+            stroke_color: Color32::BLACK,
+            stroke_width: 1.0,
+        }
+    }
+}
+
 // Uber H3 Cell
 pub struct Hexagon {
     h3cell: String,
+    style: HexagonStyle,
 }
 
 impl Hexagon {
@@ -17,17 +40,65 @@ impl Hexagon {
         Some(walkers::lat_lon(latlng.lat(), latlng.lng()))
     }
 
-    // /// Returns the boundary coordinates (lat, lng) of the hexagon.
-    // pub fn boundary_coordinates(&self) -> Option<Vec<(f64, f64)>> {
-    //     let cell_index = CellIndex::from_str(&self.h3cell).ok()?;
-
-    //     let boundary = cell_index.boundary();
-    //     let coords: Vec<(f64, f64)> = boundary
-    //         .iter()
-    //         .map(|latlng| (latlng.lat(), latlng.lng()))
-    //         .collect();
-    //     Some(coords)
-    // }
+    /// Returns the boundary coordinates (lat, lng) of the hexagon (5 or more vertices for pentagons).
+    ///
+    /// Cells crossing the antimeridian have their negative longitudes shifted by 360 degrees,
+    /// so the ring is continuous.
+    pub fn boundary_coordinates(&self) -> Option<Vec<(f64, f64)>> {
+        let cell_index = CellIndex::from_str(&self.h3cell).ok()?;
+
+        let boundary = cell_index.boundary();
+        let mut coords: Vec<(f64, f64)> = boundary
+            .iter()
+            .map(|latlng| (latlng.lat(), latlng.lng()))
+            .collect();
+
+        if crosses_antimeridian(&coords) {
+            for (_, lng) in coords.iter_mut() {
+                if *lng < 0.0 {
+                    *lng += 360.0;
+                }
+            }
+        }
+        Some(coords)
+    }
+}
+
+fn crosses_antimeridian(coords: &[(f64, f64)]) -> bool {
+    let min = coords
+        .iter()
+        .map(|(_, lng)| *lng)
+        .fold(f64::INFINITY, f64::min);
+    let max = coords
+        .iter()
+        .map(|(_, lng)| *lng)
+        .fold(f64::NEG_INFINITY, f64::max);
+    max - min > 180.0
+}
+
+// Draws the polygon, returning its screen points (None when outside the visible area)
+fn draw_polygon(
+    ui: &Ui,
+    projector: &Projector,
+    coords: &[(f64, f64)],
+    style: &HexagonStyle,
+) -> Option<Vec<Pos2>> {
+    let points: Vec<Pos2> = coords
+        .iter()
+        .map(|&(lat, lng)| projector.project(walkers::lat_lon(lat, lng)).to_pos2())
+        .collect();
+
+    // Skip cells entirely outside the visible area
+    if !ui.clip_rect().intersects(Rect::from_points(&points)) {
+        return None;
+    }
+
+    ui.painter().add(Shape::convex_polygon(
+        points.clone(),
+        style.fill_color.gamma_multiply(style.fill_opacity),
+        Stroke::new(style.stroke_width, style.stroke_color),
+    ));
+    Some(points)
 }
 
 impl Place for Hexagon {
@@ -36,42 +107,114 @@ impl Place for Hexagon {
     }
 
     fn draw(&self, ui: &Ui, projector: &Projector) {
-        let pos = self.position().unwrap_or_else(|| Position::new(0.0, 0.0));
-        let screen_position = projector.project(pos).to_pos2();
-
-        ui.painter().circle(
-            screen_position,
-            5.0,
-            Color32::BLUE.gamma_multiply(0.8),
-            Stroke::new(2., Color32::BLACK.gamma_multiply(0.8)),
-        );
+        self.draw_visible(ui, projector);
+    }
+}
+
+impl Hexagon {
+    /// Draws the hexagon, returning the screen polygons which were visible.
+    fn draw_visible(&self, ui: &Ui, projector: &Projector) -> Vec<Vec<Pos2>> {
+        let Some(coords) = self.boundary_coordinates() else {
+            return Vec::new();
+        };
+
+        let mut visible = Vec::new();
+        visible.extend(draw_polygon(ui, projector, &coords, &self.style));
+
+        // Cells crossing the antimeridian are also drawn on the western side of the map
+        if coords.iter().any(|(_, lng)| *lng > 180.0) {
+            let western: Vec<(f64, f64)> = coords
+                .iter()
+                .map(|&(lat, lng)| (lat, lng - 360.0))
+                .collect();
+            visible.extend(draw_polygon(ui, projector, &western, &self.style));
+        }
+        visible
     }
 }
 
 // Hexagon Plot Plugin
-pub struct HexagonPlot {
+pub struct HexagonPlot<'a> {
     h3cells: Vec<String>,
+    // Per Cell Fill Colors (default Style Fill Color when empty)
+    colors: Vec<Color32>,
+    style: HexagonStyle,
+    // DataFrame Row of each Cell
+    rows: &'a [usize],
+    selected_rows: Option<&'a BTreeSet<usize>>,
+    hovered: Option<&'a mut HoveredFeature>,
 }
 
-impl HexagonPlot {
-    pub fn new(h3cells: Vec<String>) -> Self {
-        Self { h3cells }
+impl<'a> HexagonPlot<'a> {
+    pub fn new(h3cells: Vec<String>, colors: Vec<Color32>, style: HexagonStyle) -> Self {
+        Self {
+            h3cells,
+            colors,
+            style,
+            rows: &[],
+            selected_rows: None,
+            hovered: None,
+        }
+    }
+
+    /// Tracks the cell rows, highlighting the selected rows and reporting the hovered one.
+    pub fn with_interaction(
+        mut self,
+        rows: &'a [usize],
+        selected_rows: &'a BTreeSet<usize>,
+        hovered: &'a mut HoveredFeature,
+    ) -> Self {
+        self.rows = rows;
+        self.selected_rows = Some(selected_rows);
+        self.hovered = Some(hovered);
+        self
+    }
+
+    fn is_selected(&self, row: Option<usize>) -> bool {
+        row.zip(self.selected_rows)
+            .is_some_and(|(row, selected_rows)| selected_rows.contains(&row))
     }
 }
 
-impl Plugin for HexagonPlot {
+impl Plugin for HexagonPlot<'_> {
     fn run(
-        self: Box<Self>,
+        mut self: Box<Self>,
         ui: &mut Ui,
-        _: &Response,
+        response: &Response,
         projector: &Projector,
         _map_memory: &MapMemory,
     ) {
-        self.h3cells.iter().for_each(|h3cell| {
+        let pointer = response.hover_pos();
+
+        for (i, h3cell) in self.h3cells.iter().enumerate() {
+            let row = self.rows.get(i).copied();
+            let selected = self.is_selected(row);
             let hexagon = Hexagon {
                 h3cell: h3cell.clone(),
+                style: HexagonStyle {
+                    fill_color: self.colors.get(i).copied().unwrap_or(self.style.fill_color),
+                    stroke_color: if selected {
+                        HIGHLIGHT_COLOR
+                    } else {
+                        self.style.stroke_color
+                    },
+                    stroke_width: if selected {
+                        self.style.stroke_width.max(1.0) + 2.0
+                    } else {
+                        self.style.stroke_width
+                    },
+                    ..self.style
+                },
             };
-            hexagon.draw(ui, projector);
-        });
+            let visible = hexagon.draw_visible(ui, projector);
+
+            if let (Some(pointer), Some(row), Some(hovered)) = (pointer, row, &mut self.hovered)
+                && let Some(polygon) = visible.iter().find(|p| polygon_contains(p, pointer))
+            {
+                // Overlapping cells resolve to the one whose center is nearest
+                let center = Rect::from_points(polygon).center();
+                hovered.offer(row, center.distance(pointer));
+            }
+        }
     }
 }
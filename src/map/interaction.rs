@@ -0,0 +1,206 @@
+use ::eframe::egui::{Color32, Pos2, Response, Shape, Stroke, Ui};
+use walkers::{MapMemory, Plugin, Position, Projector};
+
+pub const HIGHLIGHT_COLOR: Color32 = Color32::YELLOW;
+
+// Map Feature (DataFrame Row) under the Pointer, offered by each Plot Plugin
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HoveredFeature {
+    pub row: Option<usize>,
+    distance: f32,
+}
+
+impl HoveredFeature {
+    /// Records the row if it is closer to the pointer than the current one.
+    pub fn offer(&mut self, row: usize, distance: f32) {
+        if self.row.is_none() || distance < self.distance {
+            self.row = Some(row);
+            self.distance = distance;
+        }
+    }
+}
+
+/// Whether the point is inside the polygon (ray casting).
+pub fn polygon_contains(polygon: &[Pos2], point: Pos2) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Distance from the point to the segment between a and b.
+pub fn distance_to_segment(point: Pos2, a: Pos2, b: Pos2) -> f32 {
+    let ab = b - a;
+    let t = if ab.length_sq() > 0.0 {
+        ((point - a).dot(ab) / ab.length_sq()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    point.distance(a + t * ab)
+}
+
+// Pointer Drag behaviour on the Map
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SelectionTool {
+    #[default]
+    Pan,
+    Rectangle,
+    Lasso,
+}
+
+impl SelectionTool {
+    pub const ALL: [SelectionTool; 3] = [
+        SelectionTool::Pan,
+        SelectionTool::Rectangle,
+        SelectionTool::Lasso,
+    ];
+}
+
+impl std::fmt::Display for SelectionTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectionTool::Pan => write!(f, "Pan"),
+            SelectionTool::Rectangle => write!(f, "Rectangle"),
+            SelectionTool::Lasso => write!(f, "Lasso"),
+        }
+    }
+}
+
+// Draws a Rectangle or Lasso by dragging, reporting the finished polygon
+pub struct SelectionPlot<'a> {
+    tool: SelectionTool,
+    // Dragged positions (both corners for a Rectangle)
+    path: &'a mut Vec<Position>,
+    completed: &'a mut Option<Vec<Position>>,
+}
+
+impl<'a> SelectionPlot<'a> {
+    pub fn new(
+        tool: SelectionTool,
+        path: &'a mut Vec<Position>,
+        completed: &'a mut Option<Vec<Position>>,
+    ) -> Self {
+        Self {
+            tool,
+            path,
+            completed,
+        }
+    }
+
+    // Polygon vertices of the current path
+    fn polygon(&self) -> Vec<Position> {
+        match (self.tool, self.path.first(), self.path.last()) {
+            (SelectionTool::Rectangle, Some(start), Some(end)) => vec![
+                *start,
+                walkers::lon_lat(end.x(), start.y()),
+                *end,
+                walkers::lon_lat(start.x(), end.y()),
+            ],
+            _ => self.path.clone(),
+        }
+    }
+}
+
+impl Plugin for SelectionPlot<'_> {
+    fn run(
+        self: Box<Self>,
+        ui: &mut Ui,
+        response: &Response,
+        projector: &Projector,
+        _map_memory: &MapMemory,
+    ) {
+        if self.tool == SelectionTool::Pan {
+            return;
+        }
+
+        if response.drag_started() {
+            self.path.clear();
+        }
+        if response.dragged()
+            && let Some(pointer) = response.interact_pointer_pos()
+        {
+            let position = projector.unproject(pointer.to_vec2());
+            if self.tool == SelectionTool::Rectangle && self.path.len() > 1 {
+                self.path.truncate(1);
+            }
+            self.path.push(position);
+        }
+
+        let polygon = self.polygon();
+        if polygon.len() > 2 {
+            let points: Vec<Pos2> = polygon
+                .iter()
+                .map(|p| projector.project(*p).to_pos2())
+                .collect();
+            ui.painter().add(Shape::closed_line(
+                points,
+                Stroke::new(2.0, HIGHLIGHT_COLOR),
+            ));
+        }
+
+        if response.drag_stopped() {
+            if polygon.len() > 2 {
+                *self.completed = Some(polygon);
+            }
+            self.path.clear();
+        }
+    }
+}
+
+// Reports the (south west, north east) corners of the visible Map area
+pub struct ViewportBounds<'a> {
+    bounds: &'a mut Option<(Position, Position)>,
+}
+
+impl<'a> ViewportBounds<'a> {
+    pub fn new(bounds: &'a mut Option<(Position, Position)>) -> Self {
+        Self { bounds }
+    }
+}
+
+impl Plugin for ViewportBounds<'_> {
+    fn run(
+        self: Box<Self>,
+        _ui: &mut Ui,
+        response: &Response,
+        projector: &Projector,
+        _map_memory: &MapMemory,
+    ) {
+        let south_west = projector.unproject(response.rect.left_bottom().to_vec2());
+        let north_east = projector.unproject(response.rect.right_top().to_vec2());
+        *self.bounds = Some((south_west, north_east));
+    }
+}
+
+// Reports the Map position under the pointer
+pub struct PointerPosition<'a> {
+    position: &'a mut Option<Position>,
+}
+
+impl<'a> PointerPosition<'a> {
+    pub fn new(position: &'a mut Option<Position>) -> Self {
+        Self { position }
+    }
+}
+
+impl Plugin for PointerPosition<'_> {
+    fn run(
+        self: Box<Self>,
+        _ui: &mut Ui,
+        response: &Response,
+        projector: &Projector,
+        _map_memory: &MapMemory,
+    ) {
+        *self.position = response
+            .hover_pos()
+            .map(|pointer| projector.unproject(pointer.to_vec2()));
+    }
+}
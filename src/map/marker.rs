@@ -1,50 +1,400 @@
-use ::eframe::egui::{Color32, Response, Stroke, Ui};
-use walkers::{MapMemory, Plugin, Position, Projector};
-use walkers_extras::Place;
+use ::eframe::egui::{
+    Align2, Color32, FontId, Mesh, Pos2, Response, Shape, Stroke, Ui, Vec2, vec2,
+};
+use polars::prelude::*;
+use std::collections::BTreeSet;
+use std::f32::consts::TAU;
+use walkers::{MapMemory, Plugin, Projector};
 
-// Marker
-pub struct Marker {
-    position: Position,
+use crate::chart::data::numeric_values;
+use crate::df::frequency::value_counts;
+use crate::map::cluster::{PointIndex, VisiblePoints};
+use crate::map::interaction::{HIGHLIGHT_COLOR, HoveredFeature};
+
+// Categories of the shape column given their own shape
+const MAX_SHAPE_CATEGORIES: usize = 12;
+// Extra pointer tolerance around a marker for hovering
+const HOVER_TOLERANCE: f32 = 3.0;
+const CIRCLE_SEGMENTS: usize = 12;
+const CLUSTER_COLOR: Color32 = Color32::from_rgb(49, 130, 189);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarkerShape {
+    #[default]
+    Circle,
+    Square,
+    Triangle,
+}
+
+impl MarkerShape {
+    pub const ALL: [MarkerShape; 3] = [
+        MarkerShape::Circle,
+        MarkerShape::Square,
+        MarkerShape::Triangle,
+    ];
+
+    // Outline around the unit circle, with about the area of the unit circle
+    fn outline(&self) -> Vec<Vec2> {
+        match self {
+            MarkerShape::Circle => unit_circle().to_vec(),
+            MarkerShape::Square => [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                .iter()
+                .map(|&(x, y)| 0.89 * vec2(x, y))
+                .collect(),
+            // Pointing up
+            MarkerShape::Triangle => (0..3)
+                .map(|i| 1.35 * Vec2::angled(TAU * i as f32 / 3.0 - TAU / 4.0))
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for MarkerShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarkerShape::Circle => write!(f, "Circle"),
+            MarkerShape::Square => write!(f, "Square"),
+            MarkerShape::Triangle => write!(f, "Triangle"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkerStyle {
+    pub radius: f32,
+    // Numeric column scaling the radius between the min and max radius
+    pub size_column: Option<String>,
+    pub min_radius: f32,
+    pub max_radius: f32,
+    pub opacity: f32,
+    pub stroke_color: Color32,
+    pub stroke_width: f32,
+    pub shape: MarkerShape,
+    // Column whose categories are mapped to shapes (the style shape for the others)
+    pub shape_column: Option<String>,
+    pub shape_mapping: Vec<(String, MarkerShape)>,
+}
+
+impl Default for MarkerStyle {
+    fn default() -> Self {
+        Self {
+            radius: 5.0,
+            size_column: None,
+            min_radius: 3.0,
+            max_radius: 15.0,
+            opacity: 0.8,
+            stroke_color: Color32::BLACK,
+            stroke_width: 2.0,
+            shape: MarkerShape::Circle,
+            shape_column: None,
+            shape_mapping: Vec::new(),
+        }
+    }
+}
+
+impl MarkerStyle {
+    /// Whether the per row radii or shapes differ between the styles.
+    pub fn row_styles_differ(&self, other: &MarkerStyle) -> bool {
+        self.size_column != other.size_column
+            || self.min_radius != other.min_radius
+            || self.max_radius != other.max_radius
+            || self.shape != other.shape
+            || self.shape_column != other.shape_column
+            || self.shape_mapping != other.shape_mapping
+    }
+}
+
+/// Radius of each row, scaled linearly by the size column values (min radius for nulls).
+pub fn marker_radii(df: &DataFrame, style: &MarkerStyle) -> Result<Vec<f32>, String> {
+    let Some(size_column) = &style.size_column else {
+        return Ok(Vec::new());
+    };
+    let values = numeric_values(df, size_column)?;
+    let finite = values.iter().flatten().filter(|v| v.is_finite());
+    let min = finite.clone().copied().fold(f64::INFINITY, f64::min);
+    let max = finite.copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = if max > min { max - min } else { 1.0 };
+
+    let radius_range = style.max_radius - style.min_radius;
+    Ok(values
+        .iter()
+        .map(|v| match v {
+            Some(v) if v.is_finite() => {
+                style.min_radius + radius_range * ((v - min) / range) as f32
+            }
+            _ => style.min_radius,
+        })
+        .collect())
+}
+
+/// Most frequent categories of the column, each given the next shape in turn.
+pub fn shape_mapping(
+    df: &DataFrame,
+    column_name: &str,
+) -> Result<Vec<(String, MarkerShape)>, String> {
+    let counts = value_counts(df, column_name).map_err(|e| e.to_string())?;
+    Ok(counts
+        .iter()
+        .filter_map(|vc| vc.value.clone())
+        .take(MAX_SHAPE_CATEGORIES)
+        .enumerate()
+        .map(|(i, category)| (category, MarkerShape::ALL[i % MarkerShape::ALL.len()]))
+        .collect())
+}
+
+/// Shape of each row from the category mapping of the shape column.
+pub fn marker_shapes(df: &DataFrame, style: &MarkerStyle) -> Result<Vec<MarkerShape>, String> {
+    let Some(shape_column) = &style.shape_column else {
+        return Ok(Vec::new());
+    };
+    let values = df
+        .column(shape_column)
+        .and_then(|c| c.cast(&DataType::String))
+        .map_err(|e| e.to_string())?;
+    let values = values.str().map_err(|e| e.to_string())?;
+    Ok(values
+        .iter()
+        .map(|v| {
+            v.and_then(|v| style.shape_mapping.iter().find(|(c, _)| c == v))
+                .map_or(style.shape, |(_, shape)| *shape)
+        })
+        .collect())
+}
+
+fn unit_circle() -> [Vec2; CIRCLE_SEGMENTS] {
+    std::array::from_fn(|i| Vec2::angled(TAU * i as f32 / CIRCLE_SEGMENTS as f32))
 }
 
-impl Place for Marker {
-    fn position(&self) -> Position {
-        self.position
+// Filled (convex) outline as a triangle fan
+fn add_disc(mesh: &mut Mesh, outline: &[Vec2], center: Pos2, radius: f32, color: Color32) {
+    let first = mesh.vertices.len() as u32;
+    mesh.colored_vertex(center, color);
+    for offset in outline {
+        mesh.colored_vertex(center + radius * *offset, color);
     }
+    let n = outline.len() as u32;
+    for i in 0..n {
+        mesh.add_triangle(first, first + 1 + i, first + 1 + (i + 1) % n);
+    }
+}
 
-    fn draw(&self, ui: &Ui, projector: &Projector) {
-        let screen_position = projector.project(self.position).to_pos2();
+// Outline band between the inner and outer radius
+fn add_ring(mesh: &mut Mesh, outline: &[Vec2], center: Pos2, radii: (f32, f32), color: Color32) {
+    let first = mesh.vertices.len() as u32;
+    for offset in outline {
+        mesh.colored_vertex(center + radii.0 * *offset, color);
+        mesh.colored_vertex(center + radii.1 * *offset, color);
+    }
+    let n = outline.len() as u32;
+    for i in 0..n {
+        let (inner, outer) = (first + 2 * i, first + 2 * i + 1);
+        let next = first + 2 * ((i + 1) % n);
+        mesh.add_triangle(inner, outer, next + 1);
+        mesh.add_triangle(inner, next + 1, next);
+    }
+}
 
-        ui.painter().circle(
-            screen_position,
-            5.0,
-            Color32::BLUE.gamma_multiply(0.8),
-            Stroke::new(2., Color32::BLACK.gamma_multiply(0.8)),
+// Marker with the style's outline, as drawn by the painter
+fn add_marker(
+    mesh: &mut Mesh,
+    outline: &[Vec2],
+    (center, radius): (Pos2, f32),
+    color: Color32,
+    style: &MarkerStyle,
+) {
+    let half_stroke = style.stroke_width / 2.0;
+    let inner = (radius - half_stroke).max(0.0);
+    add_disc(
+        mesh,
+        outline,
+        center,
+        inner,
+        color.gamma_multiply(style.opacity),
+    );
+    if style.stroke_width > 0.0 {
+        add_ring(
+            mesh,
+            outline,
+            center,
+            (inner, radius + half_stroke),
+            style.stroke_color.gamma_multiply(style.opacity),
         );
     }
 }
 
+fn cluster_radius(count: usize) -> f32 {
+    10.0 + 4.0 * (count as f32).log10()
+}
+
+fn cluster_label(count: usize) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", count as f64 / 1e3),
+        _ => format!("{:.1}M", count as f64 / 1e6),
+    }
+}
+
 // Point Plot Plugin
-pub struct PointPlot {
-    points: Vec<Position>,
+//
+// Points outside the view are culled with the index, and visible points are batched
+// into a single mesh, clustered into counted bubbles when there are too many to draw.
+pub struct PointPlot<'a> {
+    index: &'a PointIndex,
+    // DataFrame Row of each Point
+    rows: &'a [usize],
+    // Per Row Colors (default Blue when empty)
+    row_colors: &'a [Color32],
+    style: &'a MarkerStyle,
+    // Per Row Radii and Shapes (the style radius and shape when empty)
+    row_radii: &'a [f32],
+    row_shapes: &'a [MarkerShape],
+    selected_rows: Option<&'a BTreeSet<usize>>,
+    hovered: Option<&'a mut HoveredFeature>,
 }
 
-impl PointPlot {
-    pub fn new(points: Vec<Position>) -> Self {
-        Self { points }
+impl<'a> PointPlot<'a> {
+    pub fn new(
+        index: &'a PointIndex,
+        rows: &'a [usize],
+        row_colors: &'a [Color32],
+        style: &'a MarkerStyle,
+    ) -> Self {
+        Self {
+            index,
+            rows,
+            row_colors,
+            style,
+            row_radii: &[],
+            row_shapes: &[],
+            selected_rows: None,
+            hovered: None,
+        }
+    }
+
+    /// Sizes and shapes the markers by row.
+    pub fn with_row_styles(mut self, row_radii: &'a [f32], row_shapes: &'a [MarkerShape]) -> Self {
+        self.row_radii = row_radii;
+        self.row_shapes = row_shapes;
+        self
+    }
+
+    /// Highlights the selected rows and reports the hovered one.
+    pub fn with_interaction(
+        mut self,
+        selected_rows: &'a BTreeSet<usize>,
+        hovered: &'a mut HoveredFeature,
+    ) -> Self {
+        self.selected_rows = Some(selected_rows);
+        self.hovered = Some(hovered);
+        self
+    }
+
+    fn color(&self, row: usize) -> Color32 {
+        self.row_colors.get(row).copied().unwrap_or(Color32::BLUE)
+    }
+
+    fn radius(&self, row: usize) -> f32 {
+        self.row_radii
+            .get(row)
+            .copied()
+            .unwrap_or(self.style.radius)
+    }
+
+    fn is_selected(&self, row: usize) -> bool {
+        self.selected_rows
+            .is_some_and(|selected_rows| selected_rows.contains(&row))
+    }
+
+    // Draws (point, position) markers in one mesh, returning the (row, position, radius) of each
+    fn draw_markers(&self, ui: &Ui, markers: &[(usize, Pos2)]) -> Vec<(usize, Pos2, f32)> {
+        let outlines = MarkerShape::ALL.map(|shape| shape.outline());
+        let mut mesh = Mesh::default();
+        let mut drawn = Vec::with_capacity(markers.len());
+        for &(point, pos) in markers {
+            let Some(&row) = self.rows.get(point) else {
+                continue;
+            };
+            let shape = self
+                .row_shapes
+                .get(row)
+                .copied()
+                .unwrap_or(self.style.shape);
+            let radius = self.radius(row);
+            let outline = &outlines[shape as usize];
+            add_marker(
+                &mut mesh,
+                outline,
+                (pos, radius),
+                self.color(row),
+                self.style,
+            );
+            drawn.push((row, pos, radius));
+        }
+        ui.painter().add(Shape::mesh(mesh));
+        drawn
     }
 }
 
-impl Plugin for PointPlot {
+impl Plugin for PointPlot<'_> {
     fn run(
-        self: Box<Self>,
+        mut self: Box<Self>,
         ui: &mut Ui,
-        _: &Response,
+        response: &Response,
         projector: &Projector,
         _map_memory: &MapMemory,
     ) {
-        self.points.iter().for_each(|pos| {
-            Marker { position: *pos }.draw(ui, projector);
-        });
+        let markers = match self.index.visible(projector, response.rect) {
+            VisiblePoints::Markers(markers) => self.draw_markers(ui, &markers),
+            VisiblePoints::Clusters(clusters) => {
+                let (single, grouped): (Vec<_>, Vec<_>) =
+                    clusters.into_iter().partition(|c| c.point.is_some());
+
+                let circle = unit_circle();
+                let mut mesh = Mesh::default();
+                for cluster in &grouped {
+                    let radius = cluster_radius(cluster.count);
+                    add_disc(&mut mesh, &circle, cluster.center, radius, CLUSTER_COLOR);
+                    add_ring(
+                        &mut mesh,
+                        &circle,
+                        cluster.center,
+                        (radius, radius + 2.0),
+                        Color32::WHITE.gamma_multiply(0.8),
+                    );
+                }
+                ui.painter().add(Shape::mesh(mesh));
+                for cluster in &grouped {
+                    ui.painter().text(
+                        cluster.center,
+                        Align2::CENTER_CENTER,
+                        cluster_label(cluster.count),
+                        FontId::proportional(12.0),
+                        Color32::WHITE,
+                    );
+                }
+
+                let single: Vec<(usize, Pos2)> = single
+                    .iter()
+                    .filter_map(|c| Some((c.point?, c.center)))
+                    .collect();
+                self.draw_markers(ui, &single)
+            }
+        };
+
+        for &(row, pos, radius) in &markers {
+            if self.is_selected(row) {
+                ui.painter()
+                    .circle_stroke(pos, radius + 3.0, Stroke::new(3., HIGHLIGHT_COLOR));
+            }
+        }
+
+        if let (Some(pointer), Some(hovered)) = (response.hover_pos(), &mut self.hovered) {
+            for &(row, pos, radius) in &markers {
+                let distance = pos.distance(pointer);
+                if distance <= radius + HOVER_TOLERANCE {
+                    hovered.offer(row, distance);
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,323 @@
+use ::eframe::egui::{Align2, Color32, FontId, Pos2, Response, Shape, Stroke, Ui, vec2};
+use h3o::{LatLng, Resolution};
+use std::f64::consts::PI;
+use walkers::{MapMemory, Plugin, Position, Projector};
+
+const MEASURE_COLOR: Color32 = Color32::from_rgb(220, 20, 60);
+// WGS84 ellipsoid
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+const WGS84_B: f64 = WGS84_A * (1.0 - WGS84_F);
+// Mean and authalic (equal area) Earth radius, in meters
+const MEAN_RADIUS: f64 = 6_371_008.8;
+const AUTHALIC_RADIUS: f64 = 6_371_007.2;
+
+// Clicking on the Map measures a path or an area
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MeasureTool {
+    #[default]
+    Off,
+    Distance,
+    Area,
+}
+
+impl MeasureTool {
+    pub const ALL: [MeasureTool; 3] = [MeasureTool::Off, MeasureTool::Distance, MeasureTool::Area];
+}
+
+impl std::fmt::Display for MeasureTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeasureTool::Off => write!(f, "Off"),
+            MeasureTool::Distance => write!(f, "Distance"),
+            MeasureTool::Area => write!(f, "Area"),
+        }
+    }
+}
+
+/// Geodesic distance in meters on the WGS84 ellipsoid (Vincenty).
+pub fn geodesic_distance(a: Position, b: Position) -> f64 {
+    vincenty_distance(a, b).unwrap_or_else(|| haversine_distance(a, b))
+}
+
+// None when the iteration doesn't converge (nearly antipodal points)
+fn vincenty_distance(a: Position, b: Position) -> Option<f64> {
+    let l = (b.x() - a.x()).to_radians();
+    let u1 = ((1.0 - WGS84_F) * a.y().to_radians().tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * b.y().to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = l;
+    for _ in 0..200 {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+        .sqrt();
+        if sin_sigma == 0.0 {
+            return Some(0.0);
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+        // Zero for equatorial lines
+        let cos_2sigma_m = if cos_sq_alpha != 0.0 {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+        } else {
+            0.0
+        };
+        let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+        let previous = lambda;
+        lambda = l
+            + (1.0 - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+
+        if (lambda - previous).abs() < 1e-12 {
+            let u_sq = cos_sq_alpha * (WGS84_A.powi(2) - WGS84_B.powi(2)) / WGS84_B.powi(2);
+            let big_a =
+                1.0 + u_sq / 16_384.0 * (4_096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+            let big_b = u_sq / 1_024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+            let delta_sigma = big_b
+                * sin_sigma
+                * (cos_2sigma_m
+                    + big_b / 4.0
+                        * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                            - big_b / 6.0
+                                * cos_2sigma_m
+                                * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+            return Some(WGS84_B * big_a * (sigma - delta_sigma));
+        }
+    }
+    None
+}
+
+// Great circle distance in meters on the mean Earth sphere
+fn haversine_distance(a: Position, b: Position) -> f64 {
+    let (lat1, lat2) = (a.y().to_radians(), b.y().to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (b.x() - a.x()).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * MEAN_RADIUS * h.sqrt().min(1.0).asin()
+}
+
+/// Geodesic length in meters of the path through the points.
+pub fn path_length(points: &[Position]) -> f64 {
+    points
+        .windows(2)
+        .map(|segment| geodesic_distance(segment[0], segment[1]))
+        .sum()
+}
+
+/// Area in square meters of the polygon on the authalic sphere.
+pub fn polygon_area(polygon: &[Position]) -> f64 {
+    if polygon.len() < 3 {
+        return 0.0;
+    }
+    let total: f64 = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| {
+            // Longitude difference the short way, across the antimeridian
+            let d_lon = ((b.x() - a.x()).to_radians() + PI).rem_euclid(2.0 * PI) - PI;
+            d_lon * (2.0 + a.y().to_radians().sin() + b.y().to_radians().sin())
+        })
+        .sum();
+    (total * AUTHALIC_RADIUS * AUTHALIC_RADIUS / 2.0).abs()
+}
+
+pub fn format_distance(meters: f64) -> String {
+    if meters < 1_000.0 {
+        format!("{:.1} m", meters)
+    } else {
+        format!("{:.3} km", meters / 1_000.0)
+    }
+}
+
+pub fn format_area(square_meters: f64) -> String {
+    if square_meters < 1_000_000.0 {
+        format!("{:.0} m²", square_meters)
+    } else {
+        format!("{:.3} km²", square_meters / 1_000_000.0)
+    }
+}
+
+/// "lat, lon" with micro degree precision.
+pub fn format_position(position: Position) -> String {
+    format!("{:.6}, {:.6}", position.y(), position.x())
+}
+
+/// H3 cell containing the position at the resolution.
+pub fn position_cell(position: Position, resolution: u8) -> Option<String> {
+    let resolution = Resolution::try_from(resolution).ok()?;
+    let latlng = LatLng::new(position.y(), position.x()).ok()?;
+    Some(latlng.to_cell(resolution).to_string())
+}
+
+/// Summary of the measured distance or area, None until there is something to measure.
+pub fn measurement_summary(tool: MeasureTool, points: &[Position]) -> Option<String> {
+    match tool {
+        MeasureTool::Distance if points.len() >= 2 => Some(format!(
+            "Distance: {} ({} points)",
+            format_distance(path_length(points)),
+            points.len()
+        )),
+        MeasureTool::Area if points.len() >= 3 => {
+            let mut ring = points.to_vec();
+            ring.push(points[0]);
+            Some(format!(
+                "Area: {}, Perimeter: {}",
+                format_area(polygon_area(points)),
+                format_distance(path_length(&ring))
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// WKT LineString (or Polygon for areas) of the measured points.
+pub fn measurement_wkt(tool: MeasureTool, points: &[Position]) -> String {
+    let coords = |points: &[Position]| {
+        points
+            .iter()
+            .map(|p| format!("{} {}", p.x(), p.y()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match tool {
+        MeasureTool::Area if points.len() >= 3 => {
+            let mut ring = points.to_vec();
+            ring.push(points[0]);
+            format!("POLYGON (({}))", coords(&ring))
+        }
+        _ => format!("LINESTRING ({})", coords(points)),
+    }
+}
+
+// Measure Plot Plugin, adding clicked points and drawing the measured path or area
+pub struct MeasurePlot<'a> {
+    tool: MeasureTool,
+    points: &'a mut Vec<Position>,
+}
+
+impl<'a> MeasurePlot<'a> {
+    pub fn new(tool: MeasureTool, points: &'a mut Vec<Position>) -> Self {
+        Self { tool, points }
+    }
+}
+
+impl Plugin for MeasurePlot<'_> {
+    fn run(
+        self: Box<Self>,
+        ui: &mut Ui,
+        response: &Response,
+        projector: &Projector,
+        _map_memory: &MapMemory,
+    ) {
+        if self.tool == MeasureTool::Off {
+            return;
+        }
+        if response.clicked()
+            && let Some(pointer) = response.interact_pointer_pos()
+        {
+            self.points.push(projector.unproject(pointer.to_vec2()));
+        }
+        if self.points.is_empty() {
+            return;
+        }
+
+        // The measurement follows the pointer until the next click
+        let mut points = self.points.clone();
+        if let Some(pointer) = response.hover_pos() {
+            points.push(projector.unproject(pointer.to_vec2()));
+        }
+        let screen: Vec<Pos2> = points
+            .iter()
+            .map(|p| projector.project(*p).to_pos2())
+            .collect();
+
+        let painter = ui.painter();
+        let stroke = Stroke::new(2.0, MEASURE_COLOR);
+        if self.tool == MeasureTool::Area && screen.len() >= 3 {
+            painter.add(Shape::closed_line(screen.clone(), stroke));
+        } else {
+            painter.add(Shape::line(screen.clone(), stroke));
+        }
+        for &pos in &screen[..self.points.len()] {
+            painter.circle(pos, 4.0, Color32::WHITE, stroke);
+        }
+
+        if let (Some(summary), Some(&last)) =
+            (measurement_summary(self.tool, &points), screen.last())
+        {
+            let galley =
+                painter.layout_no_wrap(summary, FontId::proportional(13.0), Color32::BLACK);
+            let rect = Align2::LEFT_BOTTOM
+                .anchor_size(last + vec2(10.0, -10.0), galley.size())
+                .expand(3.0);
+            painter.rect_filled(rect, 3.0, Color32::WHITE.gamma_multiply(0.85));
+            painter.galley(rect.shrink(3.0).min, galley, Color32::BLACK);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Degrees, minutes and seconds to decimal degrees
+    fn dms(degrees: f64, minutes: f64, seconds: f64) -> f64 {
+        degrees.signum() * (degrees.abs() + minutes / 60.0 + seconds / 3_600.0)
+    }
+
+    #[test]
+    fn geodesic_distance_flinders_peak_to_buninyong() {
+        // Vincenty's reference example
+        let flinders_peak = walkers::lat_lon(dms(-37.0, 57.0, 3.72030), dms(144.0, 25.0, 29.52440));
+        let buninyong = walkers::lat_lon(dms(-37.0, 39.0, 10.15610), dms(143.0, 55.0, 35.38390));
+        let distance = geodesic_distance(flinders_peak, buninyong);
+        assert!((distance - 54_972.271).abs() < 1e-3, "{}", distance);
+    }
+
+    #[test]
+    fn geodesic_distance_of_same_and_antipodal_points() {
+        let point = walkers::lat_lon(51.5, -0.1);
+        assert_eq!(geodesic_distance(point, point), 0.0);
+        // Nearly antipodal, where Vincenty may not converge
+        let distance = geodesic_distance(walkers::lat_lon(0.0, 0.0), walkers::lat_lon(0.5, 179.7));
+        assert!((distance - 20_000_000.0).abs() < 50_000.0, "{}", distance);
+    }
+
+    #[test]
+    fn polygon_area_of_one_degree_cell() {
+        // R² · Δλ · (sin φ2 - sin φ1) on the authalic sphere
+        let cell = |lat: f64, lon: f64| {
+            vec![
+                walkers::lat_lon(lat, lon),
+                walkers::lat_lon(lat, lon + 1.0),
+                walkers::lat_lon(lat + 1.0, lon + 1.0),
+                walkers::lat_lon(lat + 1.0, lon),
+            ]
+        };
+        let area = polygon_area(&cell(0.0, 0.0));
+        assert!((area - 12_363_711_935.19).abs() < 1.0, "{}", area);
+        let area = polygon_area(&cell(60.0, 10.0));
+        assert!((area - 6_088_414_875.40).abs() < 1.0, "{}", area);
+        // Across the antimeridian, and in either winding
+        let mut across = cell(0.0, 179.5);
+        assert!((polygon_area(&across) - 12_363_711_935.19).abs() < 1.0);
+        across.reverse();
+        assert!((polygon_area(&across) - 12_363_711_935.19).abs() < 1.0);
+    }
+
+    #[test]
+    fn polygon_area_needs_three_points() {
+        let line = [walkers::lat_lon(0.0, 0.0), walkers::lat_lon(1.0, 1.0)];
+        assert_eq!(polygon_area(&line), 0.0);
+    }
+}
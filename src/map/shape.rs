@@ -0,0 +1,203 @@
+use ::eframe::egui::{Color32, Mesh, Pos2, Rect, Response, Shape, Stroke, Ui};
+use std::collections::BTreeSet;
+use walkers::{MapMemory, Plugin, Projector};
+
+use crate::map::geometry::{Coord, Geometry};
+use crate::map::interaction::{
+    HIGHLIGHT_COLOR, HoveredFeature, distance_to_segment, polygon_contains,
+};
+
+const LINE_WIDTH: f32 = 2.5;
+// Extra pointer tolerance around points and lines for hovering
+const HOVER_TOLERANCE: f32 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeometryStyle {
+    pub fill_color: Color32,
+    pub fill_opacity: f32,
+    pub stroke_color: Color32,
+    pub stroke_width: f32,
+    pub point_radius: f32,
+}
+
+impl Default for GeometryStyle {
+    fn default() -> Self {
+        Self {
+            fill_color: Color32::BLUE,
+            fill_opacity: 0.4,
+            stroke_color: Color32::BLACK,
+            stroke_width: 1.0,
+            point_radius: 5.0,
+        }
+    }
+}
+
+// WKT/WKB Geometry Plot Plugin of Points, LineStrings and Polygons
+pub struct GeometryPlot<'a> {
+    geometries: &'a [Geometry],
+    // DataFrame Row of each Geometry
+    rows: &'a [usize],
+    // Per Row Colors (the style fill color when empty)
+    row_colors: &'a [Color32],
+    style: GeometryStyle,
+    selected_rows: Option<&'a BTreeSet<usize>>,
+    hovered: Option<&'a mut HoveredFeature>,
+}
+
+impl<'a> GeometryPlot<'a> {
+    pub fn new(
+        geometries: &'a [Geometry],
+        rows: &'a [usize],
+        row_colors: &'a [Color32],
+        style: GeometryStyle,
+    ) -> Self {
+        Self {
+            geometries,
+            rows,
+            row_colors,
+            style,
+            selected_rows: None,
+            hovered: None,
+        }
+    }
+
+    /// Highlights the selected rows and reports the hovered one.
+    pub fn with_interaction(
+        mut self,
+        selected_rows: &'a BTreeSet<usize>,
+        hovered: &'a mut HoveredFeature,
+    ) -> Self {
+        self.selected_rows = Some(selected_rows);
+        self.hovered = Some(hovered);
+        self
+    }
+
+    fn color(&self, row: usize) -> Color32 {
+        self.row_colors
+            .get(row)
+            .copied()
+            .unwrap_or(self.style.fill_color)
+    }
+
+    fn is_selected(&self, row: usize) -> bool {
+        self.selected_rows
+            .is_some_and(|selected_rows| selected_rows.contains(&row))
+    }
+}
+
+fn project(projector: &Projector, coords: &[Coord]) -> Vec<Pos2> {
+    coords
+        .iter()
+        .map(|&(lat, lon)| projector.project(walkers::lat_lon(lat, lon)).to_pos2())
+        .collect()
+}
+
+impl Plugin for GeometryPlot<'_> {
+    fn run(
+        mut self: Box<Self>,
+        ui: &mut Ui,
+        response: &Response,
+        projector: &Projector,
+        _map_memory: &MapMemory,
+    ) {
+        let clip_rect = ui.clip_rect();
+        let pointer = response.hover_pos();
+        let stroke = Stroke::new(self.style.stroke_width, self.style.stroke_color);
+        let highlight = Stroke::new(3.0, HIGHLIGHT_COLOR);
+
+        // Polygon fills are batched into one mesh, drawn below the outlines, lines and points
+        let mut mesh = Mesh::default();
+        let mut shapes = Vec::new();
+        let mut hovered_rows = Vec::new();
+
+        for (geometry, &row) in self.geometries.iter().zip(self.rows) {
+            let color = self.color(row);
+            let selected = self.is_selected(row);
+            match geometry {
+                Geometry::Point(coord) => {
+                    let center = project(projector, std::slice::from_ref(coord))[0];
+                    let radius = self.style.point_radius;
+                    if !clip_rect.expand(radius).contains(center) {
+                        continue;
+                    }
+                    shapes.push(Shape::circle_filled(center, radius, color));
+                    shapes.push(Shape::circle_stroke(center, radius, stroke));
+                    if selected {
+                        shapes.push(Shape::circle_stroke(center, radius + 3.0, highlight));
+                    }
+                    if let Some(pointer) = pointer {
+                        let distance = center.distance(pointer);
+                        if distance <= radius + HOVER_TOLERANCE {
+                            hovered_rows.push((row, distance));
+                        }
+                    }
+                }
+                Geometry::LineString(coords) => {
+                    let points = project(projector, coords);
+                    if !clip_rect.intersects(Rect::from_points(&points)) {
+                        continue;
+                    }
+                    if selected {
+                        shapes.push(Shape::line(
+                            points.clone(),
+                            Stroke::new(LINE_WIDTH + 4.0, HIGHLIGHT_COLOR),
+                        ));
+                    }
+                    shapes.push(Shape::line(points.clone(), Stroke::new(LINE_WIDTH, color)));
+                    if let Some(pointer) = pointer {
+                        let distance = points
+                            .windows(2)
+                            .map(|segment| distance_to_segment(pointer, segment[0], segment[1]))
+                            .fold(f32::INFINITY, f32::min);
+                        if distance <= LINE_WIDTH + HOVER_TOLERANCE {
+                            hovered_rows.push((row, distance));
+                        }
+                    }
+                }
+                Geometry::Polygon { rings, triangles } => {
+                    let rings: Vec<Vec<Pos2>> =
+                        rings.iter().map(|ring| project(projector, ring)).collect();
+                    let Some(exterior) = rings.first() else {
+                        continue;
+                    };
+                    if !clip_rect.intersects(Rect::from_points(exterior)) {
+                        continue;
+                    }
+
+                    let fill = color.gamma_multiply(self.style.fill_opacity);
+                    let first = mesh.vertices.len() as u32;
+                    for &pos in exterior {
+                        mesh.colored_vertex(pos, fill);
+                    }
+                    for [a, b, c] in triangles {
+                        mesh.add_triangle(first + a, first + b, first + c);
+                    }
+                    let outline = if selected { highlight } else { stroke };
+                    for ring in &rings {
+                        shapes.push(Shape::closed_line(ring.clone(), outline));
+                    }
+
+                    // Points and lines inside a polygon are preferred when hovering
+                    if let Some(pointer) = pointer
+                        && polygon_contains(exterior, pointer)
+                        && !rings[1..]
+                            .iter()
+                            .any(|hole| polygon_contains(hole, pointer))
+                    {
+                        hovered_rows.push((row, HOVER_TOLERANCE + LINE_WIDTH));
+                    }
+                }
+            }
+        }
+
+        let painter = ui.painter();
+        painter.add(Shape::mesh(mesh));
+        painter.extend(shapes);
+
+        if let Some(hovered) = &mut self.hovered {
+            for (row, distance) in hovered_rows {
+                hovered.offer(row, distance);
+            }
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use h3o::{CellIndex, LatLng};
+use polars::prelude::*;
+use std::collections::BTreeSet;
+use std::str::FromStr;
+use walkers::Position;
+
+use crate::map::columns::MapColumns;
+use crate::map::extract::{geometry_rows, h3_cell_rows, lat_lon_rows};
+
+// Area drawn on the Map, filtering rows by their coordinates, H3 cell or geometry centers
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpatialFilter {
+    // (lat, lon) vertices
+    pub polygon: Vec<(f64, f64)>,
+    pub columns: MapColumns,
+}
+
+impl SpatialFilter {
+    pub fn new(polygon: &[Position], columns: MapColumns) -> Self {
+        Self {
+            polygon: polygon.iter().map(|p| (p.y(), p.x())).collect(),
+            columns,
+        }
+    }
+
+    /// Rectangle between the (south west, north east) corners, clamped to valid coordinates.
+    pub fn from_bounds(south_west: Position, north_east: Position, columns: MapColumns) -> Self {
+        let (south, west) = (south_west.y().max(-90.0), south_west.x().max(-180.0));
+        let (north, east) = (north_east.y().min(90.0), north_east.x().min(180.0));
+        Self {
+            polygon: vec![(south, west), (south, east), (north, east), (north, west)],
+            columns,
+        }
+    }
+}
+
+/// Whether the (lat, lon) point is inside the (lat, lon) polygon (ray casting).
+pub fn polygon_contains(polygon: &[(f64, f64)], point: (f64, f64)) -> bool {
+    let (lat, lon) = point;
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for i in 0..polygon.len() {
+        let ((a_lat, a_lon), (b_lat, b_lon)) = (polygon[i], polygon[j]);
+        if (a_lat > lat) != (b_lat > lat)
+            && lon < (b_lon - a_lon) * (lat - a_lat) / (b_lat - a_lat) + a_lon
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Center (lat, lon) of an H3 cell.
+pub fn cell_center(h3cell: &str) -> Option<(f64, f64)> {
+    let latlng = LatLng::from(CellIndex::from_str(h3cell).ok()?);
+    Some((latlng.lat(), latlng.lng()))
+}
+
+/// Rows whose coordinates, H3 cell centers or geometry centers fall inside the polygon.
+pub fn rows_in_polygon(
+    df: &DataFrame,
+    columns: &MapColumns,
+    polygon: &[(f64, f64)],
+) -> PolarsResult<BTreeSet<usize>> {
+    polars_ensure!(
+        !columns.is_empty(),
+        ComputeError: "no latitude/longitude, H3 or geometry columns selected"
+    );
+    let mut rows = BTreeSet::new();
+
+    if let Some((lat, lon)) = columns.lat_lon() {
+        let extracted = lat_lon_rows(df, &lat, &lon)?;
+        rows.extend(
+            extracted
+                .rows
+                .iter()
+                .zip(extracted.values)
+                .filter(|(_, point)| polygon_contains(polygon, *point))
+                .map(|(row, _)| *row),
+        );
+    }
+    if let Some(h3cell) = &columns.h3cell {
+        let extracted = h3_cell_rows(df, h3cell)?;
+        rows.extend(
+            extracted
+                .rows
+                .iter()
+                .zip(extracted.values)
+                .filter(|(_, cell)| cell_center(cell).is_some_and(|c| polygon_contains(polygon, c)))
+                .map(|(row, _)| *row),
+        );
+    }
+    if let Some(geometry) = &columns.geometry {
+        let extracted = geometry_rows(df, geometry)?;
+        rows.extend(
+            extracted
+                .rows
+                .iter()
+                .zip(extracted.values)
+                .filter(|(_, part)| part.center().is_some_and(|c| polygon_contains(polygon, c)))
+                .map(|(row, _)| *row),
+        );
+    }
+
+    Ok(rows)
+}
+
+/// Keeps the rows inside the filter polygon.
+pub fn spatial_filter_dataframe(df: &DataFrame, filter: &SpatialFilter) -> PolarsResult<DataFrame> {
+    let rows = rows_in_polygon(df, &filter.columns, &filter.polygon)?;
+    let mask = BooleanChunked::from_iter_values(
+        "spatial_filter".into(),
+        (0..df.height()).map(|row| rows.contains(&row)),
+    );
+    df.filter(&mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (lat, lon) square from (0, 0) to (10, 10)
+    const SQUARE: [(f64, f64); 4] = [(0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0)];
+
+    #[test]
+    fn square_contains_inner_points() {
+        assert!(polygon_contains(&SQUARE, (5.0, 5.0)));
+        assert!(!polygon_contains(&SQUARE, (5.0, 15.0)));
+        assert!(!polygon_contains(&SQUARE, (-5.0, 5.0)));
+        assert!(!polygon_contains(&[], (5.0, 5.0)));
+        assert!(!polygon_contains(&SQUARE[..2], (0.0, 5.0)));
+    }
+
+    #[test]
+    fn concave_notch_is_outside() {
+        // U shape open to the north, the notch spans lon 4..6 above lat 2
+        let u_shape = [
+            (0.0, 0.0),
+            (0.0, 10.0),
+            (10.0, 10.0),
+            (10.0, 6.0),
+            (2.0, 6.0),
+            (2.0, 4.0),
+            (10.0, 4.0),
+            (10.0, 0.0),
+        ];
+        assert!(polygon_contains(&u_shape, (5.0, 2.0)));
+        assert!(polygon_contains(&u_shape, (5.0, 8.0)));
+        assert!(polygon_contains(&u_shape, (1.0, 5.0)));
+        assert!(!polygon_contains(&u_shape, (5.0, 5.0)));
+    }
+
+    #[test]
+    fn ray_through_vertices_counts_once() {
+        let diamond = [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)];
+        assert!(polygon_contains(&diamond, (0.0, 0.0)));
+        assert!(!polygon_contains(&diamond, (0.0, -2.0)));
+        assert!(!polygon_contains(&diamond, (0.0, 2.0)));
+    }
+
+    #[test]
+    fn shared_edge_belongs_to_one_polygon() {
+        let east = SQUARE.map(|(lat, lon)| (lat, lon + 10.0));
+        let containing = |point| {
+            [&SQUARE, &east]
+                .into_iter()
+                .filter(|polygon| polygon_contains(&polygon[..], point))
+                .count()
+        };
+        assert_eq!(containing((5.0, 10.0)), 1);
+        assert!(containing((0.0, 10.0)) <= 1);
+        assert!(containing((10.0, 10.0)) <= 1);
+    }
+
+    #[test]
+    fn filter_keeps_rows_inside_bounds() {
+        let df = df!(
+            "lat" => [Some(5.0), Some(20.0), None, Some(1.0)],
+            "lon" => [Some(5.0), Some(5.0), Some(5.0), Some(9.0)],
+            "name" => ["in", "north", "null", "corner"],
+        )
+        .unwrap();
+        let columns = MapColumns {
+            latitude: Some("lat".to_string()),
+            longitude: Some("lon".to_string()),
+            ..Default::default()
+        };
+        let filter = SpatialFilter::from_bounds(
+            walkers::lon_lat(0.0, 0.0),
+            walkers::lon_lat(10.0, 10.0),
+            columns.clone(),
+        );
+
+        let filtered = spatial_filter_dataframe(&df, &filter).unwrap();
+        let names: Vec<Option<&str>> = filtered
+            .column("name")
+            .unwrap()
+            .str()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(names, vec![Some("in"), Some("corner")]);
+
+        let without_columns = SpatialFilter::new(&[], MapColumns::default());
+        assert!(spatial_filter_dataframe(&df, &without_columns).is_err());
+    }
+
+    #[test]
+    fn bounds_are_clamped() {
+        let filter = SpatialFilter::from_bounds(
+            walkers::lon_lat(-200.0, -100.0),
+            walkers::lon_lat(200.0, 100.0),
+            MapColumns::default(),
+        );
+        assert_eq!(
+            filter.polygon,
+            vec![
+                (-90.0, -180.0),
+                (-90.0, 180.0),
+                (90.0, 180.0),
+                (90.0, -180.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn cells_filter_by_center() {
+        let cell = "8928308280fffff";
+        let (lat, lon) = cell_center(cell).unwrap();
+        assert!((lat - 37.776).abs() < 0.01 && (lon + 122.419).abs() < 0.01);
+        assert_eq!(cell_center("bad"), None);
+
+        let df = df!("h3" => [Some(cell), None, Some("bad")]).unwrap();
+        let columns = MapColumns {
+            h3cell: Some("h3".to_string()),
+            ..Default::default()
+        };
+        let around = [
+            (lat - 1.0, lon - 1.0),
+            (lat - 1.0, lon + 1.0),
+            (lat + 1.0, lon + 1.0),
+            (lat + 1.0, lon - 1.0),
+        ];
+        assert_eq!(
+            rows_in_polygon(&df, &columns, &around).unwrap(),
+            BTreeSet::from([0])
+        );
+    }
+}
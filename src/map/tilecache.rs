@@ -0,0 +1,282 @@
+use ::eframe::egui::{Context, Rect, pos2};
+use lru::LruCache;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::f64::consts::PI;
+use std::fs;
+use std::io;
+use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use walkers::{Position, Style, Tile, TileId, TilePiece};
+
+use crate::config::store::{app_data_dir, stable_hash};
+
+// Decoded tiles kept in memory
+const MAX_CACHED_TILES: NonZeroUsize = NonZeroUsize::new(256).unwrap();
+// Zoom levels an ancestor tile is scaled up over, when the tile itself is missing
+const MAX_OVERZOOM: u8 = 6;
+// Web Mercator latitude limit
+const MAX_LATITUDE: f64 = 85.051_128_78;
+
+/// Directory of the tile cache of all sources.
+pub fn tile_cache_root() -> PathBuf {
+    app_data_dir()
+        .unwrap_or_else(|| std::env::temp_dir().join("parqr"))
+        .join("tiles")
+}
+
+/// HTTP cache directory of a tile source, each source (and custom URL) is cached separately.
+pub fn source_cache_dir(source_key: &str) -> PathBuf {
+    tile_cache_root().join(stable_hash(source_key))
+}
+
+/// Deletes all cached tiles.
+pub fn clear_tile_cache() -> io::Result<()> {
+    let root = tile_cache_root();
+    if root.exists() {
+        fs::remove_dir_all(root)?;
+    }
+    Ok(())
+}
+
+/// Deletes the oldest tiles (of all sources) until the cache fits the size limit.
+///
+/// Expired tiles are kept otherwise, as they are still shown when offline.
+pub fn evict_tile_cache(max_bytes: u64) {
+    let Ok(sources) = fs::read_dir(tile_cache_root()) else {
+        return;
+    };
+    // (written, size, source directory, key) of the cached responses
+    let mut entries: Vec<(u128, u64, PathBuf, String)> = sources
+        .flatten()
+        .map(|source| source.path())
+        .filter(|dir| dir.is_dir())
+        .flat_map(|dir| {
+            cacache::list_sync(&dir)
+                .flatten()
+                .map(|entry| (entry.time, entry.size as u64, dir.clone(), entry.key))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let mut total: u64 = entries.iter().map(|(_, size, _, _)| size).sum();
+    if total <= max_bytes {
+        return;
+    }
+    entries.sort_by_key(|(time, _, _, _)| *time);
+    for (_, size, dir, key) in entries {
+        if total <= max_bytes {
+            break;
+        }
+        let removed = cacache::RemoveOpts::new()
+            .remove_fully(true)
+            .remove_sync(&dir, &key);
+        if removed.is_ok() {
+            total -= size;
+        }
+    }
+}
+
+// Leading fields of the responses stored by the HTTP cache of walkers (http-cache),
+// bincode ignores the remaining fields
+#[derive(Deserialize)]
+struct CachedEntry {
+    response: CachedResponse,
+}
+
+#[derive(Deserialize)]
+struct CachedResponse {
+    body: Vec<u8>,
+}
+
+/// Cached response body of the tile URL, regardless of its expiry.
+pub fn read_cached_tile(dir: &Path, url: &str) -> Option<Vec<u8>> {
+    // Key of GET requests in http-cache
+    let bytes = cacache::read_sync(dir, format!("GET:{}", url)).ok()?;
+    let entry: CachedEntry = bincode::deserialize(&bytes).ok()?;
+    Some(entry.response.body)
+}
+
+// Reads and decodes Tiles on a background thread, keeping the recently used ones in memory
+pub struct TileReader {
+    requests: Sender<TileId>,
+    loaded: Receiver<(TileId, Option<Tile>)>,
+    // Decoded tiles, None for missing ones
+    cache: LruCache<TileId, Option<Tile>>,
+    pending: HashSet<TileId>,
+}
+
+impl TileReader {
+    pub fn new(
+        mut read: impl FnMut(TileId) -> Option<Vec<u8>> + Send + 'static,
+        ctx: Context,
+    ) -> Self {
+        let (requests, requested) = mpsc::channel::<TileId>();
+        let (sender, loaded) = mpsc::channel();
+        thread::spawn(move || {
+            // Ends once the reader is dropped
+            for tile_id in requested {
+                let tile = read(tile_id).and_then(|bytes| {
+                    Tile::new(&bytes, &Style::default(), tile_id.zoom, &ctx).ok()
+                });
+                if sender.send((tile_id, tile)).is_err() {
+                    return;
+                }
+                ctx.request_repaint();
+            }
+        });
+        Self {
+            requests,
+            loaded,
+            cache: LruCache::new(MAX_CACHED_TILES),
+            pending: HashSet::new(),
+        }
+    }
+
+    fn receive(&mut self) {
+        for (tile_id, tile) in self.loaded.try_iter() {
+            self.pending.remove(&tile_id);
+            self.cache.put(tile_id, tile);
+        }
+    }
+
+    /// The tile, or None while it is read or when it is missing.
+    pub fn get(&mut self, tile_id: TileId) -> Option<Tile> {
+        self.receive();
+        if let Some(tile) = self.cache.get(&tile_id) {
+            return tile.clone();
+        }
+        if self.pending.insert(tile_id) && self.requests.send(tile_id).is_err() {
+            self.pending.remove(&tile_id);
+        }
+        None
+    }
+
+    /// Whether the tile was read, and is missing.
+    pub fn is_missing(&self, tile_id: TileId) -> bool {
+        matches!(self.cache.peek(&tile_id), Some(None))
+    }
+
+    /// The tile, or else the scaled up part of its closest ancestor in memory.
+    ///
+    /// Ancestors are only read once the tile turned out missing.
+    pub fn piece(&mut self, tile_id: TileId) -> Option<TilePiece> {
+        let mut tile_id = tile_id;
+        let mut uv = Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0));
+        let mut read = true;
+        for _ in 0..=MAX_OVERZOOM {
+            let tile = if read {
+                self.get(tile_id)
+            } else {
+                self.cache.get(&tile_id).cloned().flatten()
+            };
+            if let Some(tile) = tile {
+                return Some(TilePiece::new(tile, uv));
+            }
+            read &= self.is_missing(tile_id);
+            if tile_id.zoom == 0 {
+                break;
+            }
+            let offset = pos2((tile_id.x % 2) as f32, (tile_id.y % 2) as f32);
+            uv = Rect::from_min_max(
+                pos2((uv.min.x + offset.x) / 2.0, (uv.min.y + offset.y) / 2.0),
+                pos2((uv.max.x + offset.x) / 2.0, (uv.max.y + offset.y) / 2.0),
+            );
+            tile_id = TileId {
+                x: tile_id.x / 2,
+                y: tile_id.y / 2,
+                zoom: tile_id.zoom - 1,
+            };
+        }
+        None
+    }
+}
+
+// Tile (x, y) containing the position at the zoom level
+fn tile_xy(position: Position, zoom: u8) -> (u32, u32) {
+    let n = 2f64.powi(zoom as i32);
+    let lat = position.y().clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+    let x = (position.x() + 180.0) / 360.0 * n;
+    let y = (1.0 - lat.tan().asinh() / PI) / 2.0 * n;
+    (
+        x.floor().clamp(0.0, n - 1.0) as u32,
+        y.floor().clamp(0.0, n - 1.0) as u32,
+    )
+}
+
+/// (x, y) tile ranges covering the (south west, north east) bounds at the zoom level.
+pub fn tile_ranges(
+    (south_west, north_east): (Position, Position),
+    zoom: u8,
+) -> (RangeInclusive<u32>, RangeInclusive<u32>) {
+    let (west, south) = tile_xy(south_west, zoom);
+    let (east, north) = tile_xy(north_east, zoom);
+    (
+        west.min(east)..=west.max(east),
+        north.min(south)..=north.max(south),
+    )
+}
+
+/// Number of tiles covering the bounds over the zoom levels.
+pub fn tile_count(bounds: (Position, Position), zooms: RangeInclusive<u8>) -> u64 {
+    zooms
+        .map(|zoom| {
+            let (xs, ys) = tile_ranges(bounds, zoom);
+            (*xs.end() - *xs.start() + 1) as u64 * (*ys.end() - *ys.start() + 1) as u64
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use std::collections::HashMap;
+
+    // Same leading layout as the http-cache store, with trailing fields
+    #[derive(Serialize)]
+    struct Store {
+        response: Response,
+        policy: Vec<u8>,
+    }
+
+    #[derive(Serialize)]
+    struct Response {
+        body: Vec<u8>,
+        headers: HashMap<String, String>,
+        status: u16,
+    }
+
+    #[test]
+    fn reads_body_of_cached_response() {
+        let dir = std::env::temp_dir().join(format!("parqr-tile-cache-{}", std::process::id()));
+        let url = "https://tiles.example.com/3/2/1.png";
+        let store = Store {
+            response: Response {
+                body: vec![1, 2, 3],
+                headers: HashMap::from([("expires".to_string(), "never".to_string())]),
+                status: 200,
+            },
+            policy: vec![4, 5],
+        };
+        let bytes = bincode::serialize(&store).unwrap();
+        cacache::write_sync(&dir, format!("GET:{}", url), bytes).unwrap();
+
+        assert_eq!(read_cached_tile(&dir, url), Some(vec![1, 2, 3]));
+        assert_eq!(
+            read_cached_tile(&dir, "https://tiles.example.com/0/0/0.png"),
+            None
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn tile_ranges_cover_bounds() {
+        let bounds = (walkers::lon_lat(-1.0, -1.0), walkers::lon_lat(1.0, 1.0));
+        assert_eq!(tile_ranges(bounds, 0), (0..=0, 0..=0));
+        assert_eq!(tile_ranges(bounds, 1), (0..=1, 0..=1));
+        assert_eq!(tile_count(bounds, 0..=2), 1 + 4 + 4);
+    }
+}
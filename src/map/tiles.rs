@@ -0,0 +1,587 @@
+use ::eframe::egui::{Context, Rect, pos2};
+use image::{ImageFormat, Rgba, RgbaImage};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::Cursor;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::thread;
+use walkers::sources::{Attribution, OpenStreetMap, TileSource};
+use walkers::{
+    HttpOptions, HttpTiles, LocalTiles, Position, Style, Tile, TileId, TilePiece, Tiles,
+};
+
+use crate::config::store::KeyValueStore;
+use crate::map::tilecache::{
+    TileReader, evict_tile_cache, read_cached_tile, source_cache_dir, tile_count, tile_ranges,
+};
+
+const STORE_FILE: &str = "map_tiles.tsv";
+// Tiles queued by a single pre-cache
+pub const MAX_PRECACHE_TILES: u64 = 10_000;
+// Parallel pre-cache downloads, below the walkers default of 6
+const PRECACHE_DOWNLOADS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileProvider {
+    OpenStreetMap,
+    Satellite,
+    Light,
+    Dark,
+    // XYZ URL template, e.g. of an internal tile server
+    Custom,
+    // .mbtiles file or {z}/{x}/{y}.png tile directory
+    Local,
+}
+
+impl TileProvider {
+    pub const ALL: [TileProvider; 6] = [
+        TileProvider::OpenStreetMap,
+        TileProvider::Satellite,
+        TileProvider::Light,
+        TileProvider::Dark,
+        TileProvider::Custom,
+        TileProvider::Local,
+    ];
+
+    // Persisted name
+    fn key(&self) -> &'static str {
+        match self {
+            TileProvider::OpenStreetMap => "osm",
+            TileProvider::Satellite => "satellite",
+            TileProvider::Light => "light",
+            TileProvider::Dark => "dark",
+            TileProvider::Custom => "custom",
+            TileProvider::Local => "local",
+        }
+    }
+
+    // (URL template, attribution, attribution link) of the built-in XYZ basemaps
+    fn preset(&self) -> Option<(&'static str, &'static str, &'static str)> {
+        match self {
+            TileProvider::Satellite => Some((
+                "https://server.arcgisonline.com/ArcGIS/rest/services/World_Imagery/MapServer/tile/{z}/{y}/{x}",
+                "Esri, Maxar, Earthstar Geographics",
+                "https://www.esri.com",
+            )),
+            TileProvider::Light => Some((
+                "https://basemaps.cartocdn.com/light_all/{z}/{x}/{y}.png",
+                "© OpenStreetMap contributors © CARTO",
+                "https://carto.com/attributions",
+            )),
+            TileProvider::Dark => Some((
+                "https://basemaps.cartocdn.com/dark_all/{z}/{x}/{y}.png",
+                "© OpenStreetMap contributors © CARTO",
+                "https://carto.com/attributions",
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TileProvider {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TileProvider::OpenStreetMap => write!(f, "OpenStreetMap"),
+            TileProvider::Satellite => write!(f, "Satellite"),
+            TileProvider::Light => write!(f, "Light"),
+            TileProvider::Dark => write!(f, "Dark"),
+            TileProvider::Custom => write!(f, "Custom XYZ"),
+            TileProvider::Local => write!(f, "Local Tiles"),
+        }
+    }
+}
+
+// Basemap chosen for the Map, remembered across sessions
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileSettings {
+    pub provider: TileProvider,
+    // Custom URL template with {z}, {x} and {y} placeholders
+    pub url_template: String,
+    pub attribution: String,
+    pub local_path: Option<PathBuf>,
+    // Downloaded tile cache limit, tiles expire by their HTTP cache headers
+    pub cache_size_mb: u64,
+    // Only show cached tiles
+    pub offline: bool,
+}
+
+impl Default for TileSettings {
+    fn default() -> Self {
+        Self {
+            provider: TileProvider::OpenStreetMap,
+            url_template: String::new(),
+            attribution: String::new(),
+            local_path: None,
+            cache_size_mb: 500,
+            offline: false,
+        }
+    }
+}
+
+impl TileSettings {
+    /// Returns the previously saved settings, or the OpenStreetMap default.
+    pub fn load() -> Self {
+        let store = KeyValueStore::open(STORE_FILE);
+        let text = |key: &str| store.get(key).unwrap_or_default().to_string();
+        let default = Self::default();
+        Self {
+            provider: TileProvider::ALL
+                .into_iter()
+                .find(|p| store.get("provider") == Some(p.key()))
+                .unwrap_or(TileProvider::OpenStreetMap),
+            url_template: text("url_template"),
+            attribution: text("attribution"),
+            local_path: store.get("local_path").map(PathBuf::from),
+            cache_size_mb: text("cache_size_mb")
+                .parse()
+                .unwrap_or(default.cache_size_mb),
+            offline: store.get("offline") == Some("true"),
+        }
+    }
+
+    pub fn save(&self) {
+        let mut store = KeyValueStore::open(STORE_FILE);
+        store.set("provider", self.provider.key());
+        store.set("url_template", &self.url_template);
+        store.set("attribution", &self.attribution);
+        let local_path = self.local_path.as_ref().map(|p| p.display().to_string());
+        store.set("local_path", &local_path.unwrap_or_default());
+        store.set("cache_size_mb", &self.cache_size_mb.to_string());
+        store.set("offline", &self.offline.to_string());
+    }
+
+    fn cache_dir(&self) -> PathBuf {
+        let source_key = match self.provider {
+            TileProvider::Custom => format!("custom:{}", self.url_template.trim()),
+            provider => provider.key().to_string(),
+        };
+        source_cache_dir(&source_key)
+    }
+
+    fn cache_bytes(&self) -> u64 {
+        self.cache_size_mb * 1024 * 1024
+    }
+}
+
+// XYZ Tile Source from a URL template
+#[derive(Clone)]
+struct XyzSource {
+    url_template: String,
+    attribution: &'static str,
+    attribution_url: &'static str,
+}
+
+impl TileSource for XyzSource {
+    fn tile_url(&self, tile_id: TileId) -> String {
+        self.url_template
+            .replace("{z}", &tile_id.zoom.to_string())
+            .replace("{x}", &tile_id.x.to_string())
+            .replace("{y}", &tile_id.y.to_string())
+    }
+
+    fn attribution(&self) -> Attribution {
+        Attribution {
+            text: self.attribution,
+            url: self.attribution_url,
+            logo_light: None,
+            logo_dark: None,
+        }
+    }
+}
+
+/// Creates the tiles of the chosen basemap.
+pub fn create_tiles(settings: &TileSettings, ctx: Context) -> Result<MapTiles, String> {
+    match settings.provider {
+        TileProvider::OpenStreetMap => Ok(remote_tiles(OpenStreetMap, settings, ctx)),
+        TileProvider::Custom => {
+            let url_template = settings.url_template.trim();
+            if !["{z}", "{x}", "{y}"]
+                .iter()
+                .all(|p| url_template.contains(p))
+            {
+                return Err("URL template needs {z}, {x} and {y} placeholders".to_string());
+            }
+            // Attributions are 'static in walkers, leaked once per applied source
+            let source = XyzSource {
+                url_template: url_template.to_string(),
+                attribution: Box::leak(settings.attribution.clone().into_boxed_str()),
+                attribution_url: "",
+            };
+            let mut tiles = remote_tiles(source.clone(), settings, ctx);
+            // Only self-hosted servers are bulk downloaded, public ones forbid it
+            if let MapTiles::Remote(tiles) = &mut tiles {
+                tiles.precache_source = Some(source);
+            }
+            Ok(tiles)
+        }
+        TileProvider::Local => {
+            let path = settings
+                .local_path
+                .as_ref()
+                .ok_or("No MBTiles file or tile directory chosen")?;
+            if path.is_dir() {
+                // Deprecated in favour of PMTiles, still the reader for {z}/{x}/{y}.png trees
+                #[allow(deprecated)]
+                return Ok(MapTiles::Local(Box::new(LocalTiles::new(path, ctx))));
+            }
+            Ok(MapTiles::Local(Box::new(MbTiles::open(path, ctx)?)))
+        }
+        provider => {
+            let (url_template, attribution, attribution_url) =
+                provider.preset().unwrap_or_default();
+            let source = XyzSource {
+                url_template: url_template.to_string(),
+                attribution,
+                attribution_url,
+            };
+            Ok(remote_tiles(source, settings, ctx))
+        }
+    }
+}
+
+/// Creates the tiles of the chosen basemap, falling back to OpenStreetMap on errors.
+pub fn create_tiles_or_default(
+    settings: &TileSettings,
+    ctx: Context,
+) -> (MapTiles, Option<String>) {
+    match create_tiles(settings, ctx.clone()) {
+        Ok(tiles) => (tiles, None),
+        Err(e) => (remote_tiles(OpenStreetMap, settings, ctx), Some(e)),
+    }
+}
+
+// Downloaded tiles, or only the cached ones when offline
+fn remote_tiles<S>(source: S, settings: &TileSettings, ctx: Context) -> MapTiles
+where
+    S: TileSource + Send + Sync + 'static,
+{
+    let cache_dir = settings.cache_dir();
+    let max_bytes = settings.cache_bytes();
+    thread::spawn(move || evict_tile_cache(max_bytes));
+    if settings.offline {
+        MapTiles::Offline(Box::new(OfflineTiles::new(source, cache_dir, ctx)))
+    } else {
+        MapTiles::Remote(Box::new(CachedTiles::new(
+            source, cache_dir, max_bytes, ctx,
+        )))
+    }
+}
+
+fn http_options(cache_dir: PathBuf) -> HttpOptions {
+    HttpOptions {
+        cache: Some(cache_dir),
+        ..Default::default()
+    }
+}
+
+// Basemap Tiles, downloaded (and cached), cached only, or read from disk
+pub enum MapTiles {
+    Remote(Box<CachedTiles>),
+    Offline(Box<OfflineTiles>),
+    Local(Box<dyn Tiles>),
+}
+
+impl MapTiles {
+    pub fn remote(&mut self) -> Option<&mut CachedTiles> {
+        match self {
+            MapTiles::Remote(tiles) => Some(tiles),
+            _ => None,
+        }
+    }
+
+    fn tiles(&mut self) -> &mut dyn Tiles {
+        match self {
+            MapTiles::Remote(tiles) => tiles.as_mut(),
+            MapTiles::Offline(tiles) => tiles.as_mut(),
+            MapTiles::Local(tiles) => tiles.as_mut(),
+        }
+    }
+}
+
+impl Tiles for MapTiles {
+    fn at(&mut self, tile_id: TileId) -> Option<TilePiece> {
+        self.tiles().at(tile_id)
+    }
+
+    fn attribution(&self) -> Attribution {
+        match self {
+            MapTiles::Remote(tiles) => tiles.attribution(),
+            MapTiles::Offline(tiles) => tiles.attribution(),
+            MapTiles::Local(tiles) => tiles.attribution(),
+        }
+    }
+
+    fn tile_size(&self) -> u32 {
+        match self {
+            MapTiles::Remote(tiles) => tiles.tile_size(),
+            MapTiles::Offline(tiles) => tiles.tile_size(),
+            MapTiles::Local(tiles) => tiles.tile_size(),
+        }
+    }
+}
+
+// Hatched tile shown where there is no data
+fn placeholder_texture(ctx: &Context) -> Option<Tile> {
+    let image = RgbaImage::from_fn(256, 256, |x, y| {
+        if (x + y) % 32 < 2 || x == 0 || y == 0 {
+            Rgba([190, 190, 190, 255])
+        } else {
+            Rgba([235, 235, 235, 255])
+        }
+    });
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .ok()?;
+    Tile::new(&bytes, &Style::default(), 0, ctx).ok()
+}
+
+// Downloaded Tiles in the HTTP cache, refreshed once expired by their cache headers
+pub struct CachedTiles {
+    tiles: HttpTiles,
+    ctx: Context,
+    cache_dir: PathBuf,
+    max_bytes: u64,
+    // Source of custom servers, which may be pre-cached
+    precache_source: Option<XyzSource>,
+    precache: Option<Precache>,
+}
+
+// Tiles downloaded into the cache by a separate client, one request per frame
+struct Precache {
+    tiles: HttpTiles,
+    queue: VecDeque<TileId>,
+    last: Option<TileId>,
+    pass: u64,
+}
+
+impl CachedTiles {
+    fn new<S>(source: S, cache_dir: PathBuf, max_bytes: u64, ctx: Context) -> Self
+    where
+        S: TileSource + Send + Sync + 'static,
+    {
+        Self {
+            tiles: HttpTiles::with_options(source, http_options(cache_dir.clone()), ctx.clone()),
+            ctx,
+            cache_dir,
+            max_bytes,
+            precache_source: None,
+            precache: None,
+        }
+    }
+
+    /// Whether the source is a custom server, which may be pre-cached.
+    pub fn can_precache(&self) -> bool {
+        self.precache_source.is_some()
+    }
+
+    /// Queues the tiles covering the bounds, returning how many.
+    ///
+    /// Fresh tiles are answered by the cache without downloading them again.
+    pub fn precache(
+        &mut self,
+        bounds: (Position, Position),
+        zooms: RangeInclusive<u8>,
+    ) -> Result<usize, String> {
+        let source = self
+            .precache_source
+            .clone()
+            .ok_or("Only custom tile servers can be pre-cached")?;
+        let count = tile_count(bounds, zooms.clone());
+        if count > MAX_PRECACHE_TILES {
+            return Err(format!(
+                "{} tiles exceed the pre-cache limit of {}, zoom in or lower the zoom level",
+                count, MAX_PRECACHE_TILES
+            ));
+        }
+
+        let mut queue = VecDeque::new();
+        for zoom in zooms {
+            let (xs, ys) = tile_ranges(bounds, zoom);
+            for x in xs {
+                queue.extend(ys.clone().map(|y| TileId { x, y, zoom }));
+            }
+        }
+        let queued = queue.len();
+        self.precache = Some(Precache {
+            tiles: HttpTiles::with_options(
+                source,
+                http_options(self.cache_dir.clone()),
+                self.ctx.clone(),
+            ),
+            queue,
+            last: None,
+            pass: self.ctx.cumulative_pass_nr(),
+        });
+        self.ctx.request_repaint();
+        Ok(queued)
+    }
+
+    /// Number of queued and in-flight pre-cache downloads.
+    pub fn pending(&self) -> usize {
+        self.precache.as_ref().map_or(0, |precache| {
+            precache.queue.len() + precache.tiles.stats().in_progress
+        })
+    }
+
+    pub fn cancel_precache(&mut self) {
+        self.precache = None;
+    }
+
+    // Requests the next pre-cache tile, once per frame and below the parallel limit
+    fn update_precache(&mut self) {
+        let pass = self.ctx.cumulative_pass_nr();
+        let Some(precache) = self.precache.as_mut().filter(|p| p.pass != pass) else {
+            return;
+        };
+        precache.pass = pass;
+        let in_progress = precache.tiles.stats().in_progress;
+        let next = if in_progress < PRECACHE_DOWNLOADS {
+            precache.queue.pop_front()
+        } else {
+            None
+        };
+        // Requesting the last tile again only collects the finished downloads
+        if let Some(tile_id) = next.or(precache.last) {
+            precache.tiles.at(tile_id);
+            precache.last = Some(tile_id);
+        }
+        if precache.queue.is_empty() && in_progress == 0 && next.is_none() {
+            self.precache = None;
+            let max_bytes = self.max_bytes;
+            thread::spawn(move || evict_tile_cache(max_bytes));
+        }
+        self.ctx.request_repaint();
+    }
+}
+
+impl Tiles for CachedTiles {
+    fn at(&mut self, tile_id: TileId) -> Option<TilePiece> {
+        self.update_precache();
+        self.tiles.at(tile_id)
+    }
+
+    fn attribution(&self) -> Attribution {
+        self.tiles.attribution()
+    }
+
+    fn tile_size(&self) -> u32 {
+        self.tiles.tile_size()
+    }
+}
+
+// Tiles read from the HTTP cache only, with a placeholder where there is no data
+pub struct OfflineTiles {
+    reader: TileReader,
+    attribution: Attribution,
+    tile_size: u32,
+    placeholder: Option<Tile>,
+}
+
+impl OfflineTiles {
+    fn new<S>(source: S, cache_dir: PathBuf, ctx: Context) -> Self
+    where
+        S: TileSource + Send + 'static,
+    {
+        let attribution = source.attribution();
+        let tile_size = source.tile_size();
+        let read = move |tile_id| read_cached_tile(&cache_dir, &source.tile_url(tile_id));
+        Self {
+            attribution,
+            tile_size,
+            placeholder: placeholder_texture(&ctx),
+            reader: TileReader::new(read, ctx),
+        }
+    }
+}
+
+impl Tiles for OfflineTiles {
+    fn at(&mut self, tile_id: TileId) -> Option<TilePiece> {
+        self.reader.piece(tile_id).or_else(|| {
+            let texture = self
+                .placeholder
+                .clone()
+                .filter(|_| self.reader.is_missing(tile_id))?;
+            Some(TilePiece::new(
+                texture,
+                Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)),
+            ))
+        })
+    }
+
+    fn attribution(&self) -> Attribution {
+        self.attribution.clone()
+    }
+
+    fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+}
+
+// Tiles read from an MBTiles (SQLite) file, so the Map also works without network access
+pub struct MbTiles {
+    reader: TileReader,
+    attribution: &'static str,
+}
+
+impl MbTiles {
+    pub fn open(path: &Path, ctx: Context) -> Result<Self, String> {
+        let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+        connection
+            .prepare("SELECT zoom_level, tile_column, tile_row, tile_data FROM tiles LIMIT 1")
+            .map_err(|e| format!("{} is not an MBTiles file: {}", path.display(), e))?;
+        let attribution: Option<String> = connection
+            .query_row(
+                "SELECT value FROM metadata WHERE name = 'attribution'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap_or_default();
+
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let attribution = attribution.unwrap_or_else(|| name.to_string());
+        Ok(Self {
+            reader: TileReader::new(move |tile_id| read_tile(&connection, tile_id), ctx),
+            attribution: Box::leak(attribution.into_boxed_str()),
+        })
+    }
+}
+
+fn read_tile(connection: &Connection, tile_id: TileId) -> Option<Vec<u8>> {
+    // MBTiles rows are numbered from the south (TMS)
+    let tile_row = 1u32
+        .checked_shl(tile_id.zoom as u32)?
+        .checked_sub(tile_id.y + 1)?;
+    let mut statement = connection
+        .prepare_cached(
+            "SELECT tile_data FROM tiles \
+             WHERE zoom_level = ?1 AND tile_column = ?2 AND tile_row = ?3",
+        )
+        .ok()?;
+    statement
+        .query_row(params![tile_id.zoom, tile_id.x, tile_row], |row| row.get(0))
+        .ok()
+}
+
+impl Tiles for MbTiles {
+    fn at(&mut self, tile_id: TileId) -> Option<TilePiece> {
+        self.reader.piece(tile_id)
+    }
+
+    fn attribution(&self) -> Attribution {
+        Attribution {
+            text: self.attribution,
+            url: "",
+            logo_light: None,
+            logo_dark: None,
+        }
+    }
+
+    fn tile_size(&self) -> u32 {
+        256
+    }
+}
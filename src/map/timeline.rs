@@ -0,0 +1,200 @@
+use polars::prelude::*;
+use walkers::Position;
+
+use crate::chart::data::numeric_values;
+use crate::map::cluster::PointIndex;
+use crate::map::trajectory::seconds_per_unit;
+
+// Time Column animating the Map, showing only the rows within a window of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeline {
+    pub column: Option<String>,
+    // Window length and playback step, in seconds for time columns
+    pub window: f64,
+    pub step: f64,
+    // End of the current window
+    pub position: f64,
+    pub playing: bool,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            column: None,
+            window: 3_600.0,
+            step: 600.0,
+            position: 0.0,
+            playing: false,
+        }
+    }
+}
+
+impl Timeline {
+    /// Window (start, end) at the current position.
+    pub fn window_range(&self) -> (f64, f64) {
+        (self.position - self.window, self.position)
+    }
+}
+
+// Times of the rows of the DataFrame with their overall range
+pub struct RowTimes {
+    pub times: Vec<Option<f64>>,
+    pub start: f64,
+    pub end: f64,
+    // Whether the times are seconds (of a time column)
+    pub is_time: bool,
+}
+
+/// Reads the time column as seconds (or a numeric column as is).
+pub fn row_times(df: &DataFrame, column_name: &str) -> Result<RowTimes, String> {
+    let scale = df
+        .column(column_name)
+        .ok()
+        .and_then(|c| seconds_per_unit(c.dtype()));
+    let times: Vec<Option<f64>> = numeric_values(df, column_name)?
+        .into_iter()
+        .map(|v| {
+            v.filter(|v| v.is_finite())
+                .map(|v| v * scale.unwrap_or(1.0))
+        })
+        .collect();
+
+    let (start, end) = times
+        .iter()
+        .flatten()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(start, end), &t| {
+            (start.min(t), end.max(t))
+        });
+    if !start.is_finite() {
+        return Err(format!("Column '{}' has no values", column_name));
+    }
+    Ok(RowTimes {
+        times,
+        start,
+        end,
+        is_time: scale.is_some(),
+    })
+}
+
+/// First datetime (or else date) column of the DataFrame.
+pub fn detect_time_column(df: &DataFrame) -> Option<String> {
+    let columns = df.columns();
+    columns
+        .iter()
+        .find(|c| matches!(c.dtype(), DataType::Datetime(_, _)))
+        .or_else(|| columns.iter().find(|c| c.dtype() == &DataType::Date))
+        .map(|c| c.name().to_string())
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time.
+pub fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.floor() as i64;
+    let (days, time) = (total.div_euclid(86_400), total.rem_euclid(86_400));
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+/// Formats a timeline value as a timestamp for time columns, otherwise as a number.
+pub fn format_time(value: f64, is_time: bool) -> String {
+    if is_time {
+        format_timestamp(value)
+    } else {
+        format!("{}", value)
+    }
+}
+
+// Map Features within the Time Window
+#[derive(Default)]
+pub struct WindowFeatures {
+    pub position_rows: Vec<usize>,
+    pub point_index: PointIndex,
+    pub h3cells: Vec<String>,
+    pub h3cell_rows: Vec<usize>,
+    pub h3_centroid_index: PointIndex,
+}
+
+impl WindowFeatures {
+    /// Points and H3 cells whose row time is within the [start, end] window.
+    pub fn new(
+        times: &[Option<f64>],
+        (start, end): (f64, f64),
+        (positions, position_rows): (&[Position], &[usize]),
+        (h3cells, h3_centroids, h3cell_rows): (&[String], &[Position], &[usize]),
+    ) -> Self {
+        let in_window = |row: usize| {
+            times
+                .get(row)
+                .copied()
+                .flatten()
+                .is_some_and(|t| t >= start && t <= end)
+        };
+
+        let mut window = Self::default();
+        let mut window_positions = Vec::new();
+        for (position, &row) in positions.iter().zip(position_rows) {
+            if in_window(row) {
+                window_positions.push(*position);
+                window.position_rows.push(row);
+            }
+        }
+        let mut window_centroids = Vec::new();
+        for ((h3cell, centroid), &row) in h3cells.iter().zip(h3_centroids).zip(h3cell_rows) {
+            if in_window(row) {
+                window.h3cells.push(h3cell.clone());
+                window_centroids.push(*centroid);
+                window.h3cell_rows.push(row);
+            }
+        }
+        window.point_index = PointIndex::new(&window_positions);
+        window.h3_centroid_index = PointIndex::new(&window_centroids);
+        window
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_the_epoch() {
+        assert_eq!(format_timestamp(0.0), "1970-01-01 00:00:00");
+    }
+
+    #[test]
+    fn formats_leap_days() {
+        assert_eq!(format_timestamp(951_782_400.0), "2000-02-29 00:00:00");
+        assert_eq!(format_timestamp(1_709_208_000.0), "2024-02-29 12:00:00");
+    }
+
+    #[test]
+    fn skips_february_29_of_century_non_leap_years() {
+        assert_eq!(format_timestamp(-2_203_891_200.0), "1900-03-01 00:00:00");
+        assert_eq!(format_timestamp(4_107_542_400.0), "2100-03-01 00:00:00");
+    }
+
+    #[test]
+    fn formats_times_before_the_epoch_and_fractions() {
+        assert_eq!(format_timestamp(-1.0), "1969-12-31 23:59:59");
+        assert_eq!(format_timestamp(1_735_689_599.9), "2024-12-31 23:59:59");
+    }
+}
@@ -0,0 +1,320 @@
+use ::eframe::egui::{Color32, Pos2, Rect, Response, Shape, Stroke, Ui, Vec2};
+use polars::prelude::*;
+use walkers::{MapMemory, Plugin, Position, Projector};
+
+use crate::chart::data::numeric_values;
+use crate::map::interaction::{HoveredFeature, distance_to_segment};
+use crate::ui::palette::categorical;
+
+// Screen distance between direction arrows along a track
+const ARROW_SPACING: f32 = 80.0;
+const ARROW_SIZE: f32 = 6.0;
+// Extra pointer tolerance around a track for hovering
+const HOVER_TOLERANCE: f32 = 4.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryStyle {
+    // Column identifying each track (a single track without one)
+    pub id_column: Option<String>,
+    // Time or sequence column ordering the points (row order without one)
+    pub order_column: Option<String>,
+    // Order gap splitting a track, in seconds for time columns
+    pub gap_threshold: Option<f64>,
+    pub show_arrows: bool,
+    pub line_width: f32,
+}
+
+impl Default for TrajectoryStyle {
+    fn default() -> Self {
+        Self {
+            id_column: None,
+            order_column: None,
+            gap_threshold: None,
+            show_arrows: true,
+            line_width: 2.0,
+        }
+    }
+}
+
+// Connected run of points of one track
+pub struct Track {
+    pub id: String,
+    // Indexes of the ordered points (into the map positions)
+    pub points: Vec<usize>,
+    pub color: Color32,
+}
+
+/// Seconds per physical unit of a temporal column, None for other columns.
+pub fn seconds_per_unit(dtype: &DataType) -> Option<f64> {
+    match dtype {
+        DataType::Date => Some(86_400.0),
+        DataType::Time => Some(1e-9),
+        DataType::Datetime(unit, _) | DataType::Duration(unit) => Some(match unit {
+            TimeUnit::Nanoseconds => 1e-9,
+            TimeUnit::Microseconds => 1e-6,
+            TimeUnit::Milliseconds => 1e-3,
+        }),
+        _ => None,
+    }
+}
+
+/// Connects the points (with the DataFrame row of each) into tracks by ID, in order.
+///
+/// Tracks are split where consecutive points are further apart than the gap threshold, and
+/// points without an order value are left out.
+pub fn build_tracks(
+    df: &DataFrame,
+    point_rows: &[usize],
+    style: &TrajectoryStyle,
+) -> Result<Vec<Track>, String> {
+    let ids: Vec<Option<String>> = match &style.id_column {
+        Some(id_column) => {
+            let ids = df
+                .column(id_column)
+                .and_then(|c| c.cast(&DataType::String))
+                .map_err(|e| e.to_string())?;
+            let ids = ids.str().map_err(|e| e.to_string())?;
+            ids.iter().map(|id| id.map(str::to_string)).collect()
+        }
+        None => vec![None; df.height()],
+    };
+    let orders: Vec<Option<f64>> = match &style.order_column {
+        Some(order_column) => {
+            let scale = df
+                .column(order_column)
+                .ok()
+                .and_then(|c| seconds_per_unit(c.dtype()))
+                .unwrap_or(1.0);
+            numeric_values(df, order_column)?
+                .into_iter()
+                .map(|v| v.filter(|v| v.is_finite()).map(|v| v * scale))
+                .collect()
+        }
+        None => (0..df.height()).map(|row| Some(row as f64)).collect(),
+    };
+
+    // (id, order, point) sorted into tracks
+    let mut ordered: Vec<(&Option<String>, f64, usize)> = point_rows
+        .iter()
+        .enumerate()
+        .filter_map(|(point, &row)| Some((ids.get(row)?, orders.get(row).copied()??, point)))
+        .collect();
+    ordered.sort_by(|a, b| a.0.cmp(b.0).then(a.1.total_cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+    let mut tracks: Vec<Track> = Vec::new();
+    let mut group = 0;
+    let mut previous: Option<(&Option<String>, f64)> = None;
+    for (id, order, point) in ordered {
+        let same_id = previous.is_some_and(|(previous_id, _)| previous_id == id);
+        let within_gap = previous.is_some_and(|(_, previous_order)| {
+            style
+                .gap_threshold
+                .is_none_or(|threshold| order - previous_order <= threshold)
+        });
+        if !same_id && previous.is_some() {
+            group += 1;
+        }
+        if same_id && within_gap {
+            if let Some(track) = tracks.last_mut() {
+                track.points.push(point);
+            }
+        } else {
+            tracks.push(Track {
+                id: id.clone().unwrap_or_else(|| "null".to_string()),
+                points: vec![point],
+                color: categorical(group),
+            });
+        }
+        previous = Some((id, order));
+    }
+
+    // Single points don't make a line
+    tracks.retain(|track| track.points.len() >= 2);
+    Ok(tracks)
+}
+
+// Arrow head at the position, pointing along the direction
+fn arrow(pos: Pos2, direction: Vec2, color: Color32) -> Shape {
+    let forward = direction.normalized() * ARROW_SIZE;
+    let side = forward.rot90() * 0.6;
+    Shape::convex_polygon(
+        vec![pos + forward, pos - forward + side, pos - forward - side],
+        color,
+        Stroke::new(1.0, Color32::BLACK.gamma_multiply(0.6)),
+    )
+}
+
+// Trajectory Plot Plugin, drawing each track as a polyline
+pub struct TrajectoryPlot<'a> {
+    tracks: &'a [Track],
+    positions: &'a [Position],
+    style: &'a TrajectoryStyle,
+    // Hovered track (by index)
+    hovered: Option<&'a mut HoveredFeature>,
+}
+
+impl<'a> TrajectoryPlot<'a> {
+    pub fn new(tracks: &'a [Track], positions: &'a [Position], style: &'a TrajectoryStyle) -> Self {
+        Self {
+            tracks,
+            positions,
+            style,
+            hovered: None,
+        }
+    }
+
+    /// Reports the hovered track.
+    pub fn with_interaction(mut self, hovered: &'a mut HoveredFeature) -> Self {
+        self.hovered = Some(hovered);
+        self
+    }
+}
+
+impl Plugin for TrajectoryPlot<'_> {
+    fn run(
+        mut self: Box<Self>,
+        ui: &mut Ui,
+        response: &Response,
+        projector: &Projector,
+        _map_memory: &MapMemory,
+    ) {
+        let clip_rect = ui.clip_rect();
+        let pointer = response.hover_pos();
+        let mut lines = Vec::new();
+        let mut arrows = Vec::new();
+
+        for (index, track) in self.tracks.iter().enumerate() {
+            let points: Vec<Pos2> = track
+                .points
+                .iter()
+                .filter_map(|&point| self.positions.get(point))
+                .map(|position| projector.project(*position).to_pos2())
+                .collect();
+            if !clip_rect.intersects(Rect::from_points(&points)) {
+                continue;
+            }
+
+            if self.style.show_arrows {
+                // Arrows at even spacing along the track, carried across segments
+                let mut next_arrow = ARROW_SPACING / 2.0;
+                for segment in points.windows(2) {
+                    let direction = segment[1] - segment[0];
+                    let length = direction.length();
+                    while length > 0.0 && next_arrow <= length {
+                        let pos = segment[0] + direction * (next_arrow / length);
+                        if clip_rect.contains(pos) {
+                            arrows.push(arrow(pos, direction, track.color));
+                        }
+                        next_arrow += ARROW_SPACING;
+                    }
+                    next_arrow -= length;
+                }
+            }
+
+            if let (Some(pointer), Some(hovered)) = (pointer, &mut self.hovered) {
+                let distance = points
+                    .windows(2)
+                    .map(|segment| distance_to_segment(pointer, segment[0], segment[1]))
+                    .fold(f32::INFINITY, f32::min);
+                if distance <= self.style.line_width + HOVER_TOLERANCE {
+                    hovered.offer(index, distance);
+                }
+            }
+
+            lines.push(Shape::line(
+                points,
+                Stroke::new(self.style.line_width, track.color),
+            ));
+        }
+
+        let painter = ui.painter();
+        painter.extend(lines);
+        painter.extend(arrows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(
+        id_column: Option<&str>,
+        order_column: Option<&str>,
+        gap: Option<f64>,
+    ) -> TrajectoryStyle {
+        TrajectoryStyle {
+            id_column: id_column.map(str::to_string),
+            order_column: order_column.map(str::to_string),
+            gap_threshold: gap,
+            ..Default::default()
+        }
+    }
+
+    fn track_points(tracks: &[Track]) -> Vec<(&str, Vec<usize>)> {
+        tracks
+            .iter()
+            .map(|track| (track.id.as_str(), track.points.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn points_are_grouped_by_id_in_order() {
+        let df = df!(
+            "vehicle" => [Some("b"), Some("a"), Some("b"), Some("a"), None, None],
+            "seq" => [2, 3, 1, 1, 1, 2],
+        )
+        .unwrap();
+        let rows: Vec<usize> = (0..df.height()).collect();
+        let tracks = build_tracks(&df, &rows, &style(Some("vehicle"), Some("seq"), None)).unwrap();
+        assert_eq!(
+            track_points(&tracks),
+            vec![("null", vec![4, 5]), ("a", vec![3, 1]), ("b", vec![2, 0])]
+        );
+        assert_ne!(tracks[1].color, tracks[2].color);
+    }
+
+    #[test]
+    fn gaps_split_tracks() {
+        let df = df!("seq" => [0.0, 1.0, 2.0, 10.0, 11.0, 30.0]).unwrap();
+        let rows: Vec<usize> = (0..df.height()).collect();
+        let tracks = build_tracks(&df, &rows, &style(None, Some("seq"), Some(5.0))).unwrap();
+        // The lone point after the last gap makes no line
+        assert_eq!(
+            track_points(&tracks),
+            vec![("null", vec![0, 1, 2]), ("null", vec![3, 4])]
+        );
+        // Tracks of one ID keep its color
+        assert_eq!(tracks[0].color, tracks[1].color);
+    }
+
+    #[test]
+    fn points_without_order_are_left_out() {
+        let df = df!("seq" => [Some(3.0), None, Some(1.0), Some(f64::NAN), Some(2.0)]).unwrap();
+        let rows: Vec<usize> = (0..df.height()).collect();
+        let tracks = build_tracks(&df, &rows, &style(None, Some("seq"), None)).unwrap();
+        assert_eq!(track_points(&tracks), vec![("null", vec![2, 4, 0])]);
+    }
+
+    #[test]
+    fn row_order_follows_point_rows() {
+        let df = df!("name" => ["a", "b", "c", "d"]).unwrap();
+        // Points of rows 3, 0 and 2 (row 1 has no position)
+        let tracks = build_tracks(&df, &[3, 0, 2], &style(None, None, None)).unwrap();
+        assert_eq!(track_points(&tracks), vec![("null", vec![1, 2, 0])]);
+    }
+
+    #[test]
+    fn datetime_gaps_are_in_seconds() {
+        let df = df!("time" => [0i64, 60_000, 600_000])
+            .unwrap()
+            .lazy()
+            .with_column(col("time").cast(DataType::Datetime(TimeUnit::Milliseconds, None)))
+            .collect()
+            .unwrap();
+        let tracks =
+            build_tracks(&df, &[0, 1, 2], &style(None, Some("time"), Some(120.0))).unwrap();
+        assert_eq!(track_points(&tracks), vec![("null", vec![0, 1])]);
+        assert_eq!(seconds_per_unit(&DataType::Date), Some(86_400.0));
+        assert_eq!(seconds_per_unit(&DataType::Float64), None);
+    }
+}
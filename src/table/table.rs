@@ -1,10 +1,19 @@
 use eframe::egui::{Label, TextWrapMode};
 use egui_extras::TableBody;
 use polars::frame::DataFrame;
+use std::collections::BTreeSet;
 
-pub fn render_table_body(body: TableBody, df: &DataFrame, column_names: &[String]) {
+/// Renders the rows, returning the row which was clicked.
+pub fn render_table_body(
+    body: TableBody,
+    df: &DataFrame,
+    column_names: &[String],
+    selected_rows: &BTreeSet<usize>,
+) -> Option<usize> {
     let num_rows = df.height();
+    let mut clicked_row = None;
     body.rows(20.0, num_rows, |mut row| {
+        row.set_selected(selected_rows.contains(&row.index()));
         for col_name in column_names {
             match df.column(col_name) {
                 Ok(column) => {
@@ -23,5 +32,24 @@ pub fn render_table_body(body: TableBody, df: &DataFrame, column_names: &[String
                 }
             }
         }
+        if row.response().clicked() {
+            clicked_row = Some(row.index());
+        }
     });
+    clicked_row
+}
+
+/// Returns the (column, value) pairs of a row for the given columns.
+pub fn row_values(df: &DataFrame, row: usize, column_names: &[String]) -> Vec<(String, String)> {
+    column_names
+        .iter()
+        .map(|col_name| {
+            let value = df
+                .column(col_name)
+                .and_then(|column| column.get(row))
+                .map(|any_value| any_value.to_string())
+                .unwrap_or_else(|_| "Error".to_string());
+            (col_name.clone(), value)
+        })
+        .collect()
 }
@@ -0,0 +1,187 @@
+use eframe::egui::{self, Label, ProgressBar, RichText, ScrollArea, TextWrapMode, Ui};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use crate::df::frequency::ValueCount;
+
+const ROW_HEIGHT: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrequencySort {
+    CountDescending,
+    CountAscending,
+    ValueAscending,
+    ValueDescending,
+}
+
+// Value Count Breakdown of a Column
+pub struct FrequencyView {
+    pub column_name: String,
+    counts: Vec<ValueCount>,
+    search: String,
+    sort: FrequencySort,
+    // Selected values, None for null
+    selected: HashSet<Option<String>>,
+}
+
+impl FrequencyView {
+    pub fn new(column_name: String, counts: Vec<ValueCount>) -> Self {
+        Self {
+            column_name,
+            counts,
+            search: String::new(),
+            sort: FrequencySort::CountDescending,
+            selected: HashSet::new(),
+        }
+    }
+
+    /// Replaces the counts (e.g. after the DataFrame was filtered), keeping search and selection.
+    pub fn set_counts(&mut self, counts: Vec<ValueCount>) {
+        self.counts = counts;
+        self.sort_counts();
+    }
+
+    fn sort_counts(&mut self) {
+        match self.sort {
+            FrequencySort::CountDescending => self.counts.sort_by_key(|c| Reverse(c.count)),
+            FrequencySort::CountAscending => self.counts.sort_by_key(|c| c.count),
+            FrequencySort::ValueAscending => self.counts.sort_by(|a, b| a.value.cmp(&b.value)),
+            FrequencySort::ValueDescending => self.counts.sort_by(|a, b| b.value.cmp(&a.value)),
+        }
+    }
+
+    fn toggle_sort(&mut self, ascending: FrequencySort, descending: FrequencySort) {
+        self.sort = if self.sort == descending {
+            ascending
+        } else {
+            descending
+        };
+        self.sort_counts();
+    }
+
+    /// Renders the breakdown, returning the selected values (None for null) when the user
+    /// applies them as a filter.
+    pub fn render(&mut self, ui: &mut Ui) -> Option<Vec<Option<String>>> {
+        let mut apply = None;
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} distinct values", self.counts.len()));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.search)
+                    .hint_text("Search...")
+                    .desired_width(160.0),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            let value_indicator = match self.sort {
+                FrequencySort::ValueAscending => " ⬆",
+                FrequencySort::ValueDescending => " ⬇",
+                _ => "",
+            };
+            let count_indicator = match self.sort {
+                FrequencySort::CountAscending => " ⬆",
+                FrequencySort::CountDescending => " ⬇",
+                _ => "",
+            };
+            if ui
+                .button(RichText::new(format!("Value{value_indicator}")).strong())
+                .clicked()
+            {
+                self.toggle_sort(
+                    FrequencySort::ValueAscending,
+                    FrequencySort::ValueDescending,
+                );
+            }
+            if ui
+                .button(RichText::new(format!("Count{count_indicator}")).strong())
+                .clicked()
+            {
+                self.toggle_sort(
+                    FrequencySort::CountAscending,
+                    FrequencySort::CountDescending,
+                );
+            }
+        });
+        ui.separator();
+
+        let search = self.search.to_lowercase();
+        let visible: Vec<usize> = self
+            .counts
+            .iter()
+            .enumerate()
+            .filter(|(_, vc)| {
+                search.is_empty()
+                    || vc
+                        .value
+                        .as_deref()
+                        .unwrap_or("null")
+                        .to_lowercase()
+                        .contains(&search)
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        ScrollArea::vertical()
+            .max_height(400.0)
+            .auto_shrink([false, true])
+            .show_rows(ui, ROW_HEIGHT, visible.len(), |ui, row_range| {
+                for &i in &visible[row_range] {
+                    let value_count = &self.counts[i];
+                    ui.horizontal(|ui| {
+                        let value = &value_count.value;
+                        let mut checked = self.selected.contains(value);
+                        if ui.checkbox(&mut checked, "").changed() {
+                            if checked {
+                                self.selected.insert(value.clone());
+                            } else {
+                                self.selected.remove(value);
+                            }
+                        }
+                        match value {
+                            Some(value) => {
+                                ui.add(
+                                    Label::new(value.as_str()).wrap_mode(TextWrapMode::Truncate),
+                                );
+                            }
+                            None => {
+                                ui.label(RichText::new("null").italics());
+                            }
+                        }
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.add(
+                                ProgressBar::new(value_count.fraction as f32)
+                                    .desired_width(100.0)
+                                    .text(format!("{:.1}%", value_count.fraction * 100.0)),
+                            );
+                            ui.label(value_count.count.to_string());
+                        });
+                    });
+                }
+            });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui.button("Clear Selection").clicked() {
+                self.selected.clear();
+            }
+            if ui
+                .add_enabled(
+                    !self.selected.is_empty(),
+                    egui::Button::new(format!("Filter to Selected ({})", self.selected.len())),
+                )
+                .clicked()
+            {
+                apply = Some(
+                    self.counts
+                        .iter()
+                        .filter(|vc| self.selected.contains(&vc.value))
+                        .map(|vc| vc.value.clone())
+                        .collect(),
+                );
+            }
+        });
+
+        apply
+    }
+}
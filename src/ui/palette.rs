@@ -0,0 +1,20 @@
+use eframe::egui::Color32;
+
+// Tableau 10 Categorical Palette
+pub const CATEGORICAL: [Color32; 10] = [
+    Color32::from_rgb(78, 121, 167),
+    Color32::from_rgb(242, 142, 43),
+    Color32::from_rgb(225, 87, 89),
+    Color32::from_rgb(118, 183, 178),
+    Color32::from_rgb(89, 161, 79),
+    Color32::from_rgb(237, 201, 72),
+    Color32::from_rgb(176, 122, 161),
+    Color32::from_rgb(255, 157, 167),
+    Color32::from_rgb(156, 117, 95),
+    Color32::from_rgb(186, 176, 172),
+];
+
+/// Returns the categorical color for the index, cycling through the palette.
+pub fn categorical(index: usize) -> Color32 {
+    CATEGORICAL[index % CATEGORICAL.len()]
+}
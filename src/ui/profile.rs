@@ -0,0 +1,110 @@
+use eframe::egui::{self, Color32, RichText, Sense, Stroke, Ui, Vec2};
+
+use crate::df::stats::{ColumnProfile, ColumnStats, HistogramBin};
+
+const HISTOGRAM_SIZE: Vec2 = Vec2::new(240.0, 60.0);
+
+/// Renders the summary of a single column (used for header tooltips and the profile tab).
+pub fn render_column_stats(ui: &mut Ui, stats: &ColumnStats) {
+    egui::Grid::new(format!("column_stats_{}", stats.name))
+        .num_columns(2)
+        .spacing([12.0, 2.0])
+        .show(ui, |ui| {
+            let mut row = |label: &str, value: String| {
+                ui.label(RichText::new(label).weak());
+                ui.label(value);
+                ui.end_row();
+            };
+
+            row("Type", format!("{}", stats.dtype));
+            row("Count", stats.count.to_string());
+            row("Nulls", stats.null_count.to_string());
+            row(
+                "Distinct",
+                stats
+                    .distinct_count
+                    .map_or_else(|| "-".to_string(), |n| n.to_string()),
+            );
+            row("Min", stats.min.clone().unwrap_or_else(|| "-".to_string()));
+            row("Max", stats.max.clone().unwrap_or_else(|| "-".to_string()));
+
+            if let Some(numeric) = &stats.numeric {
+                row("Mean", format!("{:.4}", numeric.mean));
+                row("Std", format!("{:.4}", numeric.std));
+                row("25%", format!("{:.4}", numeric.q25));
+                row("50%", format!("{:.4}", numeric.median));
+                row("75%", format!("{:.4}", numeric.q75));
+            }
+        });
+
+    if !stats.top_values.is_empty() {
+        ui.separator();
+        ui.label(RichText::new("Top values").weak());
+        for (value, count) in &stats.top_values {
+            ui.label(format!("{value}: {count}"));
+        }
+    }
+}
+
+/// Renders a bar histogram of the bins.
+pub fn render_histogram(ui: &mut Ui, bins: &[HistogramBin]) {
+    let (response, painter) = ui.allocate_painter(HISTOGRAM_SIZE, Sense::hover());
+    let rect = response.rect;
+    painter.rect_stroke(
+        rect,
+        0.0,
+        Stroke::new(1.0, Color32::GRAY),
+        egui::StrokeKind::Inside,
+    );
+
+    let max_count = bins.iter().map(|b| b.count).max().unwrap_or(0);
+    if bins.is_empty() || max_count == 0 {
+        return;
+    }
+
+    let bar_width = rect.width() / bins.len() as f32;
+    for (i, bin) in bins.iter().enumerate() {
+        let height = rect.height() * bin.count as f32 / max_count as f32;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(rect.left() + bar_width * i as f32, rect.bottom() - height),
+            egui::pos2(rect.left() + bar_width * (i + 1) as f32, rect.bottom()),
+        );
+        painter.rect_filled(bar.shrink2(Vec2::new(0.5, 0.0)), 0.0, Color32::LIGHT_BLUE);
+    }
+
+    if let Some(hover_pos) = response.hover_pos() {
+        let i = (((hover_pos.x - rect.left()) / bar_width) as usize).min(bins.len() - 1);
+        let bin = &bins[i];
+        response.on_hover_text(format!("[{:.4}, {:.4}): {}", bin.start, bin.end, bin.count));
+    }
+}
+
+/// Renders the profile of every column as a grid of cards.
+pub fn render_profile(ui: &mut Ui, profile: &[ColumnProfile]) {
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for column in profile {
+                    ui.group(|ui| {
+                        ui.set_width(HISTOGRAM_SIZE.x);
+                        ui.vertical(|ui| {
+                            ui.label(RichText::new(&column.name).strong());
+                            match &column.stats {
+                                Ok(stats) => {
+                                    render_column_stats(ui, stats);
+                                    if let Some(numeric) = &stats.numeric {
+                                        ui.separator();
+                                        render_histogram(ui, &numeric.histogram);
+                                    }
+                                }
+                                Err(e) => {
+                                    ui.colored_label(Color32::RED, e);
+                                }
+                            }
+                        });
+                    });
+                }
+            });
+        });
+}
@@ -1,5 +1,7 @@
 pub enum ViewTab {
     Table,
+    Profile,
+    Chart,
     Map,
     Export,
 }